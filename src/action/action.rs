@@ -5,6 +5,7 @@ use crate::{context::Context, event::{Event, Operations}, translator::parser::Op
 
 /// Action is a set of events that triggers at specific timestamps.
 /// In vinx, actions are either periodical: `every 10 frames { ... }`, or onetime: `at 42 frames { ... }`.
+/// Times in seconds or milliseconds follow the timestamps of the input: `at 90 seconds { ... }`.
 /// These types are distinguished in the Accumulator.
 /// Actions can potentially be named and based on this name enabled / disabled.
 #[derive(Debug)]
@@ -45,10 +46,10 @@ impl Action {
         s
     }
 
-    /// Tell this action, that there is a new frame.
-    /// This function is automatically ignored for disabled functions.
-    pub fn step(&mut self) {
-        self.trigger.step();
+    /// Tell this action, that there is a new frame at `time` (in milliseconds).
+    /// Counting frames is automatically ignored for disabled functions.
+    pub fn step(&mut self, time: usize) {
+        self.trigger.step(time);
     }
 
     /// Try to trigger this action
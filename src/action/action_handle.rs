@@ -1,3 +1,5 @@
+use crate::context::Context;
+
 use super::Action;
 
 /// Events return these handles, which modify the actions.
@@ -7,6 +9,10 @@ pub enum ActionHandle {
     Disable(String),
     Toggle(String),
     Stop,
+    /// Do not write the current frame to the output.
+    Skip,
+    /// Write the current frame to the output once more for each repetition.
+    Repeat(usize),
 }
 
 impl ActionHandle {
@@ -20,9 +26,18 @@ impl ActionHandle {
     }
 
     /// returns if the whole program should stop
-    pub fn trigger(&self, actions: &mut Vec<Action>) -> bool {
-        if let ActionHandle::Stop = self {
-            return true;
+    pub fn trigger(&self, actions: &mut Vec<Action>, context: &mut Context) -> bool {
+        match self {
+            ActionHandle::Stop => return true,
+            ActionHandle::Skip => {
+                context.set_frame_copies(0);
+                return false;
+            }
+            ActionHandle::Repeat(n) => {
+                context.set_frame_copies(n + 1);
+                return false;
+            }
+            _ => {}
         }
         for a in actions.iter_mut() {
             let Some(name) = a.get_name() else { continue };
@@ -37,7 +52,7 @@ impl ActionHandle {
                         a.enable();
                     }
                 }
-                ActionHandle::Stop => panic!("UNREACHABLE")
+                ActionHandle::Stop | ActionHandle::Skip | ActionHandle::Repeat(_) => panic!("UNREACHABLE")
             }
         }
         false
@@ -45,9 +60,9 @@ impl ActionHandle {
 }
 
 /// returns if the whole program should stop
-pub fn process_action_handles(handles: &mut Vec<ActionHandle>, actions: &mut Vec<Action>) -> bool {
+pub fn process_action_handles(handles: &mut Vec<ActionHandle>, actions: &mut Vec<Action>, context: &mut Context) -> bool {
     for h in handles.iter() {
-        if h.trigger(actions) {
+        if h.trigger(actions, context) {
             return true;
         }
     }
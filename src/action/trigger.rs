@@ -1,10 +1,14 @@
 use crate::variable::{Stack, Variable, VariableType};
-use crate::translator::ast;
 
-// TODO: add milliseconds
 #[derive(Debug, PartialEq, Eq)]
 pub enum TimeUnit {
+    /// Frames are counted while the trigger is enabled.
     Frame,
+    /// Presentation time of the input, `at` triggers activate at an absolute time of the video
+    /// and `every` triggers on the first frame of each period.
+    Second,
+    /// The same as `Second`.
+    Millisecond,
 }
 
 /// Time accumulator, which is supposed to activate at certain trigger time.
@@ -12,9 +16,16 @@ pub enum TimeUnit {
 /// If it is a `onetime`, it will get disabled upon its first activation.
 #[derive(Debug)]
 pub struct Trigger {
+    /// Counted frames.
     counter: usize,
+    /// Presentation time of the current frame in milliseconds.
+    time: usize,
+    /// Last activation of a periodic trigger in time units, on the grid of periods.
+    last: usize,
     trigger_time: Variable,
     unit: TimeUnit,
+    /// Presentation times (in milliseconds) the trigger is limited to, the end is exclusive.
+    window: Option<(usize, usize)>,
     onetime: bool,
     enabled: bool,
 }
@@ -22,56 +33,65 @@ pub struct Trigger {
 impl Trigger {
     pub fn new(trigger_time: Variable, unit: TimeUnit, onetime: bool) -> Self {
         assert!(trigger_time.get_type() == VariableType::Int);
-        Self { trigger_time, counter: 0, unit, onetime, enabled: true }
+        Self { trigger_time, counter: 0, time: 0, last: 0, unit, window: None, onetime, enabled: true }
     }
 
-    // Create Trigger from its ast representation
-    pub fn from(trigger: ast::Trigger, stack: &Stack) -> Self {
-        let time = match trigger.time {
-            ast::Time::Variable(name, _) => {
-                let Some (var) = stack.get_variable(&name) else {
-                    panic!("error: no such variable {name}") // TODO: friendlify
-                };
-                Variable::Named(name, var.get_type())
-            }
-            ast::Time::Number(n, _) => {
-                Variable::Static(crate::variable::VariableValue::Int(n as i32)) // TODO: make i64
-            }
-        };
-        let unit = match trigger.unit {
-            ast::Unit::Frame(_) => TimeUnit::Frame,
-            ast::Unit::Second(_) => panic!("error: time unit `second` not implemented"), // TODO: friendlify
-            ast::Unit::Millisecond(_) => panic!("error: time unit `millisecond` not implemented"),
-            // x => panic!("error: unexpected time unit `{x:?}`"),
-        };
-        Self { counter: 0, trigger_time: time, unit, onetime: trigger.onetime, enabled: trigger.active }
+    /// Only count and activate while the presentation time is in `from..to` (in milliseconds).
+    pub fn set_window(&mut self, from: usize, to: usize) {
+        self.window = Some((from, to));
     }
 
+    /// Start counting from the current frame again.
     pub fn clear(&mut self) {
         self.counter = 0;
+        self.last = self.time;
     }
 
     pub fn is_enabled(&self) -> bool {
         self.enabled
     }
 
-    /// Increase the counter.
-    pub fn step(&mut self) {
-        if !self.enabled { return; }
+    fn in_window(&self) -> bool {
+        self.window.is_none_or(|(from, to)| (from..to).contains(&self.time))
+    }
+
+    /// Increase the counter, `time` is the presentation time of the new frame in milliseconds.
+    pub fn step(&mut self, time: usize) {
+        self.time = time;
+        if !self.enabled || !self.in_window() { return; }
         match &self.unit {
             TimeUnit::Frame => self.counter += 1,
+            TimeUnit::Second | TimeUnit::Millisecond => {}
         }
     }
 
     /// If counted up to the trigger time, return `true` and modify the counter.
     pub fn activate(&mut self, stack: &Stack) -> bool {
-        if !self.enabled { return false; }
+        if !self.enabled || !self.in_window() { return false; }
         let t = self.trigger_time.get_value(stack).into_int() as usize;
-        if self.counter < t { return false; }
-        if self.onetime { self.enabled = false; } // disable onetime triggers
+        let t = if self.unit == TimeUnit::Second { t.saturating_mul(1000) } else { t };
         match &self.unit {
-            TimeUnit::Frame => self.counter = 0,
+            TimeUnit::Frame => {
+                if self.counter < t { return false; }
+                self.counter = 0;
+            }
+            TimeUnit::Second | TimeUnit::Millisecond if self.onetime => {
+                if self.time < t { return false; }
+            }
+            TimeUnit::Second | TimeUnit::Millisecond => {
+                // periods are aligned to the start of the video, so `every 2 seconds` is at 2s, 4s, ...
+                // the time can also go back (e.g. when the input is reversed)
+                let t = t.max(1);
+                if self.time >= self.last.saturating_add(t) {
+                    self.last = self.time - (self.time - self.last) % t;
+                } else if self.time.saturating_add(t) <= self.last {
+                    self.last = self.time + (self.last - self.time) % t;
+                } else {
+                    return false;
+                }
+            }
         }
+        if self.onetime { self.enabled = false; } // disable onetime triggers
         true
     }
 
@@ -93,7 +113,7 @@ mod tests {
 
     fn step_n_times(acc: &mut Trigger, n: usize) {
         for _ in 0..n {
-            acc.step();
+            acc.step(0);
         }
     }
 
@@ -153,4 +173,98 @@ mod tests {
         t.enable();
         assert!(t.activate(&s));
     }
+
+    #[test]
+    fn test_timestamps() {
+        let s = get_stack();
+        // absolute time, frames may come at any rate
+        let mut t = Trigger::new(Variable::Static(crate::variable::VariableValue::Int(2)), TimeUnit::Second, true);
+        t.step(1999);
+        assert!(!t.activate(&s));
+        t.step(2040);
+        assert!(t.activate(&s));
+        t.step(2080);
+        assert!(!t.activate(&s));
+        // once per period of the video, `t` is 5 ms
+        let mut t = Trigger::new(Variable::new("t", VariableType::Int), TimeUnit::Millisecond, false);
+        t.step(0);
+        assert!(!t.activate(&s));
+        t.step(4);
+        assert!(!t.activate(&s));
+        t.step(7);
+        assert!(t.activate(&s));
+        assert!(!t.activate(&s));
+        t.step(9);
+        assert!(!t.activate(&s));
+        // a skipped period activates only once
+        t.step(23);
+        assert!(t.activate(&s));
+        assert!(!t.activate(&s));
+    }
+
+    #[test]
+    fn test_time_going_back() {
+        let s = get_stack();
+        // `t` is 5 ms, the input is played backwards from 23 ms
+        let mut t = Trigger::new(Variable::new("t", VariableType::Int), TimeUnit::Millisecond, false);
+        t.step(23);
+        assert!(t.activate(&s));
+        t.step(21);
+        assert!(!t.activate(&s));
+        t.step(16);
+        assert!(!t.activate(&s));
+        t.step(15);
+        assert!(t.activate(&s));
+        assert!(!t.activate(&s));
+        t.step(9);
+        assert!(t.activate(&s));
+        // and forward again, from 10 ms
+        t.step(12);
+        assert!(!t.activate(&s));
+        t.step(15);
+        assert!(t.activate(&s));
+        // slow motion, the same time several times
+        t.step(15);
+        assert!(!t.activate(&s));
+    }
+
+    #[test]
+    fn test_clear_timestamps() {
+        let s = get_stack();
+        let mut t = Trigger::new(Variable::new("t", VariableType::Int), TimeUnit::Millisecond, false);
+        t.step(13);
+        t.clear();
+        assert!(!t.activate(&s));
+        t.step(17);
+        assert!(!t.activate(&s));
+        t.step(18);
+        assert!(t.activate(&s));
+    }
+
+    #[test]
+    fn test_window() {
+        let s = get_stack();
+        let mut t = Trigger::new(Variable::Static(crate::variable::VariableValue::Int(1)), TimeUnit::Frame, false);
+        t.set_window(10, 20);
+        t.step(5);
+        assert!(!t.activate(&s));
+        t.step(10);
+        assert!(t.activate(&s));
+        assert!(!t.activate(&s));
+        t.step(15);
+        assert!(t.activate(&s));
+        t.disable();
+        t.step(16);
+        assert!(!t.activate(&s));
+        t.enable();
+        t.step(20);
+        assert!(!t.activate(&s));
+        // frames are only counted in the window
+        let mut t = Trigger::new(Variable::new("t", VariableType::Int), TimeUnit::Frame, false);
+        t.set_window(10, 20);
+        (0..10).for_each(|time| t.step(time));
+        assert!(!t.activate(&s));
+        (10..15).for_each(|time| t.step(time));
+        assert!(t.activate(&s));
+    }
 }
@@ -1,8 +1,35 @@
-use crate::video::{Frame, VideoReader};
+use std::collections::VecDeque;
+
+use crate::video::{CameraTransform, Frame, Layer, SubtitleStyle, Subtitles, Target, Timeline, VideoInfo, VideoReader, transition::RunningTransition};
 
 pub struct Context<'a> {
     video_reader: Option<&'a mut VideoReader<'a>>,
     current_frame: Option<Frame>,
+    /// Which input frames are shown (speed changes, freezing, reversing).
+    timeline: Timeline,
+    /// Presentation times (in milliseconds) of the input frames read so far, by their index.
+    frame_times: Vec<usize>,
+    info: Option<VideoInfo>,
+    antialias: bool,
+    /// Camera transforms of the current frame, applied in `finish_frame`.
+    camera: Vec<CameraTransform>,
+    /// How many times is the current frame written to the output.
+    frame_copies: usize,
+    /// Transitions applied in `finish_frame` (after the camera transforms).
+    transitions: Vec<RunningTransition>,
+    /// How many past frames are kept in `history` and `original_history`.
+    history_length: usize,
+    /// Finished frames as they were written to the output, the most recent first.
+    /// A repeated frame is there once for each copy, a skipped frame is not there at all.
+    history: VecDeque<Frame>,
+    /// Frames as they were decoded, the most recent (the current frame) first.
+    original_history: VecDeque<Frame>,
+    /// Layers drawn over the frame in `finish_frame`, in the order they were created.
+    layers: Vec<Layer>,
+    /// Index of the layer drawing operations draw on, `None` is the frame itself.
+    active_layer: Option<usize>,
+    subtitles: Option<Subtitles>,
+    subtitle_style: SubtitleStyle,
 }
 
 impl<'a> Context<'a> {
@@ -11,20 +38,96 @@ impl<'a> Context<'a> {
     }
 
     pub fn new() -> Self {
-        Self { video_reader: None, current_frame: None }
+        Self::from_info(None)
+    }
+
+    /// Create a context without frames, which still knows the properties of the processed video.
+    /// This is used for evaluating sequences before the video is processed.
+    pub fn from_info(info: Option<VideoInfo>) -> Self {
+        Self {
+            video_reader: None,
+            current_frame: None,
+            timeline: Timeline::new(),
+            frame_times: vec![],
+            info,
+            antialias: true,
+            camera: vec![],
+            frame_copies: 1,
+            transitions: vec![],
+            history_length: 0,
+            history: VecDeque::new(),
+            original_history: VecDeque::new(),
+            layers: vec![],
+            active_layer: None,
+            subtitles: None,
+            subtitle_style: SubtitleStyle::new(),
+        }
     }
 
     pub fn set_reader(&mut self, reader: &'a mut VideoReader<'a>) {
+        self.info = Some(reader.info());
         self.video_reader = Some(reader);
     }
 
+    pub fn set_antialias(&mut self, antialias: bool) {
+        self.antialias = antialias;
+    }
+
+    pub fn set_subtitles(&mut self, subtitles: Option<Subtitles>) {
+        self.subtitles = subtitles;
+    }
+
+    pub fn get_subtitle_style_mut(&mut self) -> &mut SubtitleStyle {
+        &mut self.subtitle_style
+    }
+
+    pub fn set_history_length(&mut self, length: usize) {
+        self.history_length = length;
+    }
+
+    /// Whether shapes are drawn with smooth (anti-aliased) edges.
+    pub fn antialias(&self) -> bool {
+        self.antialias
+    }
+
+    /// Index of the current frame in the input, counting from 1.
+    /// Before any frame is loaded, this returns 0.
     pub fn get_frame_index(&self) -> usize {
-        self.video_reader.as_ref().expect("error: no reader given").get_frame_index()
+        self.timeline.current_index()
     }
 
+    /// Play the input at a different speed (2.0 is twice as fast), with `blend` frames are blended
+    /// instead of being duplicated or dropped.
+    pub fn set_speed(&mut self, speed: f64, blend: bool) {
+        self.timeline.set_speed(speed, blend);
+    }
+
+    /// Repeat the current input frame in the following `frames` output frames.
+    pub fn freeze(&mut self, frames: usize) {
+        self.timeline.freeze(frames);
+    }
+
+    /// Play the input frames `from` to `to` backwards once they are reached.
+    pub fn reverse(&mut self, from: usize, to: usize) {
+        self.timeline.reverse(from, to);
+    }
+
+    /// Load the frame to process, the actions are run once for each loaded frame.
     pub fn load_next_frame(&mut self) -> bool {
         let r = self.video_reader.as_mut().expect("error: could not find video reader");
-        self.current_frame = r.get_next_frame();
+        let times = &mut self.frame_times;
+        self.current_frame = self.timeline.next_frame(|| {
+            let frame = r.get_next_frame();
+            if frame.is_some() {
+                times.push(r.get_frame_time_ms());
+            }
+            frame
+        });
+        self.frame_copies = 1;
+        if let Some(frame) = &self.current_frame && self.history_length > 0 {
+            self.original_history.push_front(frame.clone());
+            self.original_history.truncate(self.history_length + 1);
+        }
         self.current_frame.is_some()
     }
 
@@ -32,6 +135,129 @@ impl<'a> Context<'a> {
         println!("{} ... {}", msg, self.current_frame.is_some());
     }
 
+    /// Set how many times is the current frame written to the output (0 skips it).
+    pub fn set_frame_copies(&mut self, copies: usize) {
+        self.frame_copies = copies;
+    }
+
+    pub fn get_frame_copies(&self) -> usize {
+        self.frame_copies
+    }
+
+    /// Queue a camera transform, it is applied to the current frame once all actions are done.
+    pub fn push_camera_transform(&mut self, t: CameraTransform) {
+        self.camera.push(t);
+    }
+
+    /// Start a transition, it progresses with every finished frame.
+    pub fn start_transition(&mut self, t: RunningTransition) {
+        self.transitions.push(t);
+    }
+
+    /// Draw on the layer of the given name (it is created if it does not exist yet).
+    /// Returns the previously selected layer for `restore_layer`, `None` is the frame itself.
+    pub fn select_layer(&mut self, name: &str) -> Option<usize> {
+        let previous = self.active_layer;
+        self.active_layer = Some(self.layer_index(name));
+        previous
+    }
+
+    /// Select again a layer returned by `select_layer`.
+    pub fn restore_layer(&mut self, layer: Option<usize>) {
+        self.active_layer = layer;
+    }
+
+    /// Layer of the given name, it is created if it does not exist yet.
+    pub fn get_layer_mut(&mut self, name: &str) -> &mut Layer {
+        let i = self.layer_index(name);
+        &mut self.layers[i]
+    }
+
+    fn layer_index(&mut self, name: &str) -> usize {
+        if let Some(i) = self.layers.iter().position(|l| l.name == name) {
+            return i;
+        }
+        let (w, h) = self.get_current_frame().dimensions();
+        self.layers.push(Layer::new(name.to_string(), w, h));
+        self.layers.len() - 1
+    }
+
+    /// What drawing operations draw on, the selected layer or the current frame.
+    pub fn get_target_mut(&mut self) -> Target<'_> {
+        match self.active_layer {
+            Some(i) => Target::Layer(&mut self.layers[i].image),
+            None => Target::Frame(self.current_frame.as_mut().expect("error: no current frame loaded")),
+        }
+    }
+
+    /// What an object on the given layer draws on, `None` is the frame itself.
+    pub fn get_layer_target_mut(&mut self, layer: Option<&str>) -> Target<'_> {
+        match layer {
+            Some(name) => Target::Layer(&mut self.get_layer_mut(name).image),
+            None => Target::Frame(self.get_current_frame_mut()),
+        }
+    }
+
+    /// Finish processing of the current frame: draw the layers over it, apply the queued camera transforms
+    /// and running transitions and remember the result in the history. Subtitles are drawn last,
+    /// they are not kept in the history.
+    pub fn finish_frame(&mut self) {
+        self.active_layer = None;
+        let Some(frame) = self.current_frame.as_mut() else {
+            self.camera.clear();
+            return;
+        };
+        // stable sort, layers with the same z-index stay in the order they were created
+        let mut order: Vec<&mut Layer> = self.layers.iter_mut().collect();
+        order.sort_by_key(|l| l.z_index);
+        for l in order {
+            l.composite(frame);
+            l.clear();
+        }
+        for t in self.camera.drain(..) {
+            t.apply(frame);
+        }
+        for t in self.transitions.iter_mut() {
+            t.apply(frame);
+        }
+        self.transitions.retain(|t| !t.is_finished());
+        for _ in 0..self.frame_copies.min(self.history_length) {
+            self.history.push_front(frame.clone());
+        }
+        self.history.truncate(self.history_length);
+        if let Some(subtitles) = &self.subtitles {
+            let time = self.get_time_ms();
+            let frame = self.current_frame.as_mut().unwrap();
+            subtitles.draw(frame, time, &self.subtitle_style);
+        }
+    }
+
+    fn expect_history(&self, ago: usize) {
+        if ago > self.history_length {
+            panic!("error: cannot get a frame {ago} frames ago, only {} frames of history are kept (see `keep Int frames of history`)", self.history_length); // TODO: friendlify
+        }
+    }
+
+    /// Finished frame `ago` frames before the current one (`ago` >= 1).
+    /// At the start of the video the oldest finished frame is returned, or the current frame if there is none.
+    pub fn get_past_frame(&self, ago: usize) -> &Frame {
+        self.expect_history(ago);
+        match self.history.get(ago.saturating_sub(1)).or(self.history.back()) {
+            Some(frame) => frame,
+            None => self.get_current_frame(),
+        }
+    }
+
+    /// Frame as it was decoded `ago` frames before the current one, 0 is the current frame before any changes.
+    /// At the start of the video the first frame is returned.
+    pub fn get_original_frame(&self, ago: usize) -> &Frame {
+        self.expect_history(ago.max(1));
+        match self.original_history.get(ago).or(self.original_history.back()) {
+            Some(frame) => frame,
+            None => self.get_current_frame(),
+        }
+    }
+
     pub fn pop_current_frame(&mut self) -> Frame {
         self.current_frame.take().expect("error: no current frame loaded")
     }
@@ -44,18 +270,42 @@ impl<'a> Context<'a> {
         self.current_frame.as_mut().expect("error: no current frame loaded")
     }
 
-    pub fn get_width(&self) -> usize {
-        let Some(video) = &self.video_reader else {
+    fn get_info(&self) -> &VideoInfo {
+        let Some(info) = &self.info else {
             panic!("error: empty context")
         };
-        video.width() as usize
+        info
+    }
+
+    pub fn get_width(&self) -> usize {
+        self.get_info().width as usize
     }
 
     pub fn get_height(&self) -> usize {
-        let Some(video) = &self.video_reader else {
-            panic!("error: empty context")
-        };
-        video.height() as usize
+        self.get_info().height as usize
+    }
+
+    /// Size of the frames, unknown when evaluating without a video.
+    pub fn frame_size(&self) -> Option<(u32, u32)> {
+        self.info.as_ref().map(|info| (info.width, info.height))
+    }
+
+    pub fn get_fps(&self) -> f64 {
+        self.get_info().fps
+    }
+
+    pub fn get_frame_count(&self) -> usize {
+        self.get_info().frame_count
+    }
+
+    /// Presentation time of the current input frame in milliseconds (the first frame is at 0).
+    /// Without a video reader, the time is given by the frame index and the frame rate.
+    pub fn get_time_ms(&self) -> usize {
+        let index = self.get_frame_index().saturating_sub(1);
+        match self.frame_times.get(index) {
+            Some(time) => *time,
+            None => (index as f64 * 1000.0 / self.get_fps()).round() as usize,
+        }
     }
 }
 
@@ -131,3 +381,43 @@ impl<'a> Context<'a> {
 //         }
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{variable::Color, video::Canvas};
+
+    fn finish(context: &mut Context, value: u8, copies: usize) {
+        context.current_frame = Some(Frame::from_pixel(1, 1, image::Rgb([value, value, value])));
+        context.set_frame_copies(copies);
+        context.finish_frame();
+    }
+
+    #[test]
+    fn test_history_follows_output() {
+        let mut context = Context::new();
+        context.set_history_length(3);
+        finish(&mut context, 1, 1);
+        finish(&mut context, 2, 0); // skipped
+        assert_eq!(context.get_past_frame(1)[(0, 0)].0[0], 1);
+        finish(&mut context, 3, 2); // repeated
+        assert_eq!(context.get_past_frame(1)[(0, 0)].0[0], 3);
+        assert_eq!(context.get_past_frame(2)[(0, 0)].0[0], 3);
+        assert_eq!(context.get_past_frame(3)[(0, 0)].0[0], 1);
+        finish(&mut context, 4, 5); // more copies than the history length
+        assert!((1..=3).all(|ago| context.get_past_frame(ago)[(0, 0)].0[0] == 4));
+    }
+
+    #[test]
+    fn test_layer_target() {
+        let mut context = Context::new();
+        context.current_frame = Some(Frame::new(2, 2));
+        let red = Color::from([255, 0, 0]);
+        context.get_layer_target_mut(Some("top")).set_color(0, 0, red);
+        context.get_layer_target_mut(None).set_color(1, 1, red);
+        assert_eq!(context.get_current_frame()[(0, 0)], Color::from([0, 0, 0]));
+        assert_eq!(context.get_current_frame()[(1, 1)], red);
+        context.finish_frame();
+        assert_eq!(context.get_current_frame()[(0, 0)], red);
+    }
+}
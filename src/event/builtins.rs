@@ -1,8 +1,8 @@
 use crate::action::ActionHandle;
 use crate::context::Context;
 use crate::variable::Variable;
-use crate::variable::{Stack, Direction, VariableValue};
-use crate::video::Drawable;
+use crate::variable::{Stack, Direction, Edges, Position, VariableValue};
+use crate::video::{Drawable, Fill, Frame, Image};
 
 pub type Builtin = fn(&mut Context, &mut Stack, &mut Vec<Variable>, &mut Vec<ActionHandle>) -> Option<VariableValue>;
 
@@ -38,6 +38,24 @@ pub fn stop(_context: &mut Context, _stack: &mut Stack, _params: &mut Vec<Variab
     None
 }
 
+/// The current frame is not written to the output.
+pub fn skip(_context: &mut Context, _stack: &mut Stack, params: &mut Vec<Variable>, action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+    expect_param_count("skip this frame", params, 0);
+    action_handles.push(ActionHandle::Skip);
+    None
+}
+
+/// The current frame is written to the output once more for each repetition.
+pub fn repeat(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+    expect_param_count("repeat this frame", params, 1);
+    let times = params[0].get_value(stack).into_int();
+    if times < 0 {
+        panic!("error: cannot repeat frame negative number of times: {times}"); // TODO: friendlify
+    }
+    action_handles.push(ActionHandle::Repeat(times as usize));
+    None
+}
+
 pub fn deactivate(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
     let op_name = "deactivate";
     expect_param_count(op_name, params, 1);
@@ -135,13 +153,18 @@ pub fn rotate_vec(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Va
     None
 }
 
+/// Frames become images (which are fully opaque).
+fn to_image(frame: &Frame) -> Image {
+    ::image::DynamicImage::from(frame.clone()).into_rgba8()
+}
+
 pub fn get_frame(context: &mut Context, _stack: &mut Stack, _params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
     if context.is_empty() { 
         panic!("error: cannot return frame when context is empty");
     }
     // return None; } // TODO: should we really return None in here?
     let frame = context.get_current_frame();
-    Some(VariableValue::Image(frame.clone()))
+    Some(VariableValue::Image(to_image(frame)))
 }
 
 pub fn draw_rect(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
@@ -150,11 +173,10 @@ pub fn draw_rect(context: &mut Context, stack: &mut Stack, params: &mut Vec<Vari
     let par1 = &params[0].get_value(stack);
     let par2 = &params[1].get_value(stack);
     let par3 = &params[2].get_value(stack);
-    let c = par1.into_color();
+    let fill = shape::fill_of(par1);
     let top_left = par2.into_pos();
     let bot_right = par3.into_pos();
-    let frame = context.get_current_frame_mut();
-    frame.draw_rect((top_left.x as usize,top_left.y as usize), (bot_right.x as usize,bot_right.y as usize), c);
+    shape::rect(context, top_left, bot_right, fill, false);
     None
 }
 
@@ -167,7 +189,7 @@ pub fn _draw_rect(r: &mut Runtime, params: &mut Vec<Variable>) -> Option<Variabl
     let c = par1.into_color();
     let top_left = par2.into_pos();
     let bot_right = par3.into_pos();
-    let frame = r.context.get_current_frame_mut();
+    let mut frame = r.context.get_target_mut();
     frame.draw_rect((top_left.x as usize,top_left.y as usize), (bot_right.x as usize,bot_right.y as usize), c);
     None
 }
@@ -181,8 +203,7 @@ pub fn draw_effect_rect(context: &mut Context, stack: &mut Stack, params: &mut V
     let e = par1.into_effect();
     let top_left = par2.into_pos();
     let bot_right = par3.into_pos();
-    let frame = context.get_current_frame_mut();
-    frame.draw_effect_rect((top_left.x as usize,top_left.y as usize), (bot_right.x as usize,bot_right.y as usize), e);
+    shape::rect(context, top_left, bot_right, Fill::Effect(e), false);
     None
 }
 
@@ -192,11 +213,10 @@ pub fn draw_rect_outline(context: &mut Context, stack: &mut Stack, params: &mut
     let par1 = &params[0].get_value(stack);
     let par2 = &params[1].get_value(stack);
     let par3 = &params[2].get_value(stack);
-    let c = par1.into_color();
+    let fill = shape::fill_of(par1);
     let top_left = par2.into_pos();
     let bot_right = par3.into_pos();
-    let frame = context.get_current_frame_mut();
-    frame.draw_rect_outline((top_left.x as usize,top_left.y as usize), (bot_right.x as usize,bot_right.y as usize), c);
+    shape::rect(context, top_left, bot_right, fill, true);
     None
 }
 
@@ -208,23 +228,24 @@ pub fn move_pos_phase(context: &mut Context, stack: &mut Stack, params: &mut Vec
     let par3 = &params[2].get_value(stack);
     let mut pos = par1.into_pos();
     let d = par2.into_direction();
-    let step = par3.into_int();
-    let width = context.get_width() as i32;
-    let height = context.get_height() as i32;
+    // the step is either Int or Float (sub-pixel motion)
+    let step = match par3 {
+        VariableValue::Float(f) => *f,
+        v => v.into_int() as f64,
+    };
+    let width = context.get_width() as f64;
+    let height = context.get_height() as f64;
+    let (mut x, mut y) = pos.exact();
     match d {
-        Direction::Left => {
-            pos.x = (pos.x-step).rem_euclid(width); 
-        }
-        Direction::Right => { 
-            pos.x = (pos.x+step).rem_euclid(width); 
-        }
-        Direction::Down => { 
-            pos.y = (pos.y+step).rem_euclid(height); 
-        }
-        Direction::Up => {
-            pos.y = (pos.y-step).rem_euclid(height); 
-        }
+        Direction::Left => x -= step,
+        Direction::Right => x += step,
+        Direction::Down => y += step,
+        Direction::Up => y -= step,
     }
+    // the velocity does not matter for wrapping
+    Edges::Wrap.constrain(&mut x, 1.0, &mut 0.0, width);
+    Edges::Wrap.constrain(&mut y, 1.0, &mut 0.0, height);
+    pos = Position::from_exact(x, y);
     params[0].set_value(stack, VariableValue::Pos(pos));
     None
 }
@@ -246,13 +267,13 @@ pub fn move_pos(context: &mut Context, stack: &mut Stack, params: &mut Vec<Varia
     let height = context.get_height() as i32;
     match d {
         Direction::Left =>
-            pos.x = (pos.x.saturating_sub(step)).max(width),
+            pos.set((pos.x.saturating_sub(step)).max(0), pos.y),
         Direction::Right =>
-            pos.x = (pos.x.saturating_add(step)).min(width),
+            pos.set((pos.x.saturating_add(step)).min(width - 1), pos.y),
         Direction::Down =>
-            pos.y = (pos.y.saturating_add(step)).min(height),
+            pos.set(pos.x, (pos.y.saturating_add(step)).min(height - 1)),
         Direction::Up =>
-            pos.y = (pos.y.saturating_sub(step)).max(height),
+            pos.set(pos.x, (pos.y.saturating_sub(step)).max(0)),
     }
     params[0].set_value(stack, VariableValue::Pos(pos));
     None
@@ -264,17 +285,68 @@ pub fn move_by(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Varia
     let par2 = &params[1].get_value(stack);
     let mut pos = par1.into_pos();
     let diff = par2.into_pos();
-    pos.x = pos.x.saturating_add(diff.x);
-    pos.y = pos.y.saturating_add(diff.y);
+    pos.set(pos.x.saturating_add(diff.x), pos.y.saturating_add(diff.y));
     params[0].set_value(stack, VariableValue::Pos(pos));
     None
 }
 
+/// Move a position or a rectangle by a velocity, then handle the edges of the frame.
+/// A bouncing object flips the velocity (when it is a variable).
+fn move_with_edges(operation: &str, context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, edges: Edges) {
+    expect_param_count(operation, params, 2);
+    let (width, height) = (context.get_width() as f64, context.get_height() as f64);
+    let (mut vx, mut vy) = params[1].get_value(stack).into_pos().exact();
+    match params[0].get_value_mut(stack) {
+        VariableValue::Pos(pos) => {
+            pos.translate(vx, vy);
+            let (mut x, mut y) = pos.exact();
+            // a position covers one pixel
+            edges.constrain(&mut x, 1.0, &mut vx, width);
+            edges.constrain(&mut y, 1.0, &mut vy, height);
+            *pos = Position::from_exact(x, y);
+        }
+        VariableValue::Rectangle(r) => {
+            r.top_left.translate(vx, vy);
+            r.bot_right.translate(vx, vy);
+            let (l, t) = r.top_left.exact();
+            let (right, bottom) = r.bot_right.exact();
+            let (mut x, mut y) = (l, t);
+            edges.constrain(&mut x, right - l, &mut vx, width);
+            edges.constrain(&mut y, bottom - t, &mut vy, height);
+            r.top_left.translate(x - l, y - t);
+            r.bot_right.translate(x - l, y - t);
+        }
+        v => panic!("error: {operation}: expected Pos or Rectangle, got {}", v.get_type()), // TODO: friendlify
+    }
+    params[1].set_value(stack, VariableValue::Pos(Position::from_exact(vx, vy)));
+}
+
+/// The position (or rectangle) stops at the edges of the frame.
+pub fn move_clamped(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+    if context.is_empty() { return None; }
+    move_with_edges("move clamping to edges", context, stack, params, Edges::Clamp);
+    None
+}
+
+/// The position (or rectangle) comes back from the opposite edge of the frame.
+pub fn move_wrapped(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+    if context.is_empty() { return None; }
+    move_with_edges("move wrapping around edges", context, stack, params, Edges::Wrap);
+    None
+}
+
+/// The position (or rectangle) is reflected at the edges of the frame and its velocity flips.
+pub fn move_bouncing(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+    if context.is_empty() { return None; }
+    move_with_edges("move bouncing off edges", context, stack, params, Edges::Bounce);
+    None
+}
+
 pub mod image {
-    use crate::variable::Color;
+    use crate::video::{Canvas, Keyable, Transformable};
 
     use super::*;
-    use ::image;
+    use ::image::{self, Pixel};
 
     pub fn draw_at(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
         expect_param_count("draw image at", params, 2);
@@ -283,11 +355,36 @@ pub mod image {
         let par2 = &params[1].get_value(stack);
         let img = par1.into_image();
         let pos = par2.into_pos();
-        let frame = context.get_current_frame_mut();
-        image::imageops::overlay(frame, img, pos.x.into(), pos.y.into());
+        let mut frame = context.get_target_mut();
+        frame.draw_image(img, pos.x.into(), pos.y.into());
+        None
+    }
+
+    /// Draw the image with its opacity multiplied by a percentage.
+    pub fn draw_at_with_opacity(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("draw image at with opacity", params, 3);
+        if context.is_empty() { return None; }
+        let img = params[0].get_value(stack).into_image();
+        let pos = params[1].get_value(stack).into_pos();
+        let opacity = params[2].get_value(stack).into_int().clamp(0, 100) as f64 / 100.0;
+        let mut frame = context.get_target_mut();
+        frame.draw_image_with_opacity(img, pos.x.into(), pos.y.into(), opacity);
         None
     }
 
+    /// Absolute difference of each channel, the result has the size of the overlap of the images.
+    pub fn difference(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("difference between images", params, 2);
+        let a = params[0].get_value(stack).into_image();
+        let b = params[1].get_value(stack).into_image();
+        let (width, height) = (a.width().min(b.width()), a.height().min(b.height()));
+        let out = Image::from_fn(width, height, |x, y| {
+            let (p, q) = (a.get_pixel(x, y), b.get_pixel(x, y));
+            image::Rgba([p[0].abs_diff(q[0]), p[1].abs_diff(q[1]), p[2].abs_diff(q[2]), 255])
+        });
+        Some(VariableValue::Image(out))
+    }
+
     pub fn draw_into(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
         expect_param_count("draw into image", params, 3);
         let par1 = params[0].get_value(stack);
@@ -296,7 +393,7 @@ pub mod image {
         let r = par2.into_rectangle();
         let par3 = params[2].get_value_mut(stack);
         let VariableValue::Image(img) = par3 else { panic!() };
-        img.draw_rect((r.top_left.x as usize,r.top_left.y as usize), (r.bot_right.x as usize, r.bot_right.y as usize), color);
+        img.fill_rect((r.top_left.x as usize,r.top_left.y as usize), (r.bot_right.x as usize, r.bot_right.y as usize), color);
         None
     }
 
@@ -306,7 +403,13 @@ pub mod image {
         let par2 = &params[1].get_value(stack);
         let img = par1.into_image();
         let name = par2.into_string();
-        if let Err(e) = img.save(name) {
+        // keep the alpha channel only when it is needed, so that formats without it can be used
+        let saved = if img.pixels().all(|p| p[3] == 255) {
+            image::DynamicImage::from(img.clone()).into_rgb8().save(name)
+        } else {
+            img.save(name)
+        };
+        if let Err(e) = saved {
             eprintln!("warning: could not save image as {name}: {e}");
         }
         None
@@ -317,7 +420,7 @@ pub mod image {
         let par1 = &params[0].get_value(stack);
         let name = par1.into_string();
         match image::open(name) {
-            Ok(i) => Some(VariableValue::Image(i.into_rgb8())),
+            Ok(i) => Some(VariableValue::Image(i.into_rgba8())),
             Err(e) => panic!("error: could not load image {name}: {e}"),
         }
     }
@@ -332,14 +435,14 @@ pub mod image {
         let in_img = par2.into_image();
         let width = in_img.width() as i32;
         let height = in_img.height() as i32;
-        let default_color = Color::from([0,0,0]); // default to black
+        let default_color = image::Rgba([0,0,0,255]); // default to black
         if bot_right.x < top_left.x {
             bot_right.x += width;
         }
         if bot_right.y < top_left.y {
             bot_right.y += height;
         }
-        let mut out_img = image::RgbImage::new((bot_right.x-top_left.x) as u32, (bot_right.y-top_left.y) as u32);
+        let mut out_img = Image::new((bot_right.x-top_left.x) as u32, (bot_right.y-top_left.y) as u32);
         for row in top_left.x..bot_right.x {
             for col in top_left.y..bot_right.y {
                 let color = if row < 0 || row >= width || col < 0 || col >= height {
@@ -367,12 +470,89 @@ pub mod image {
         if height < 0 {
             panic!("error: negative image height {height}") // TODO: user friendlify
         }
-        let mut img = image::RgbImage::new(width as u32, height as u32);
-        for p in img.pixels_mut() {
-            *p = col;
-        }
+        let img = Image::from_pixel(width as u32, height as u32, col.to_rgba());
         Some(VariableValue::Image(img))
     }
+
+    pub fn scale_to(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("scale image to", params, 3);
+        let width = params[1].get_value(stack).into_int();
+        let height = params[2].get_value(stack).into_int();
+        if width < 0 || height < 0 {
+            panic!("error: cannot scale image to negative size {width}x{height}") // TODO: user friendlify
+        }
+        params[0].get_value_mut(stack).into_image_mut().scale_to(width as u32, height as u32);
+        None
+    }
+
+    pub fn scale_by(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("scale image by", params, 2);
+        let factor = params[1].get_value(stack).into_float();
+        if factor < 0.0 {
+            panic!("error: cannot scale image by negative factor {factor}") // TODO: user friendlify
+        }
+        params[0].get_value_mut(stack).into_image_mut().scale_by(factor);
+        None
+    }
+
+    pub fn rotate(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("rotate image", params, 2);
+        let degrees = params[1].get_value(stack).into_int();
+        params[0].get_value_mut(stack).into_image_mut().rotate_by(degrees);
+        None
+    }
+
+    pub fn flip_horizontally(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("flip image horizontally", params, 1);
+        params[0].get_value_mut(stack).into_image_mut().flip_horizontally();
+        None
+    }
+
+    pub fn flip_vertically(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("flip image vertically", params, 1);
+        params[0].get_value_mut(stack).into_image_mut().flip_vertically();
+        None
+    }
+
+    /// Cut the image down to the rectangle (clipped to the image).
+    pub fn crop(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("crop image", params, 2);
+        let r = params[1].get_value(stack).into_rectangle();
+        let img = params[0].get_value_mut(stack).into_image_mut();
+        let (width, height) = (img.width() as i32, img.height() as i32);
+        let l = r.top_left.x.clamp(0, width);
+        let t = r.top_left.y.clamp(0, height);
+        let w = r.bot_right.x.clamp(l, width) - l;
+        let h = r.bot_right.y.clamp(t, height) - t;
+        *img = image::imageops::crop_imm(img, l as u32, t as u32, w as u32, h as u32).to_image();
+        None
+    }
+
+    /// Make the parts of the image similar to the color transparent (green screen).
+    pub fn key_out(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("key out", params, 3);
+        let key = params[0].get_value(stack).into_color();
+        let tolerance = params[2].get_value(stack).into_int();
+        params[1].get_value_mut(stack).into_image_mut().key_out(key, tolerance);
+        None
+    }
+
+    pub fn suppress_spill(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("suppress spill", params, 2);
+        let key = params[0].get_value(stack).into_color();
+        params[1].get_value_mut(stack).into_image_mut().suppress_spill(key);
+        None
+    }
+
+    pub fn feather(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("feather edges", params, 2);
+        let radius = params[1].get_value(stack).into_int();
+        if radius < 0 {
+            panic!("error: cannot feather edges by negative radius {radius}") // TODO: user friendlify
+        }
+        params[0].get_value_mut(stack).into_image_mut().feather(radius);
+        None
+    }
 }
 
 pub mod rectangle {
@@ -392,12 +572,9 @@ pub mod rectangle {
         if context.is_empty() { return None; }
         let par1 = &params[0].get_value(stack);
         let par2 = &params[1].get_value(stack);
-        let c = par1.into_color();
+        let fill = shape::fill_of(par1);
         let r = par2.into_rectangle();
-        let top_left = r.top_left;
-        let bot_right = r.bot_right;
-        let frame = context.get_current_frame_mut();
-        frame.draw_rect((top_left.x as usize,top_left.y as usize), (bot_right.x as usize,bot_right.y as usize), c);
+        shape::rect(context, r.top_left, r.bot_right, fill, false);
         None
     }
 
@@ -406,12 +583,9 @@ pub mod rectangle {
         if context.is_empty() { return None; }
         let par1 = &params[0].get_value(stack);
         let par2 = &params[1].get_value(stack);
-        let c = par1.into_color();
+        let fill = shape::fill_of(par1);
         let r = par2.into_rectangle();
-        let top_left = r.top_left;
-        let bot_right = r.bot_right;
-        let frame = context.get_current_frame_mut();
-        frame.draw_rect_outline((top_left.x as usize,top_left.y as usize), (bot_right.x as usize,bot_right.y as usize), c);
+        shape::rect(context, r.top_left, r.bot_right, fill, true);
         None
     }
 
@@ -421,10 +595,8 @@ pub mod rectangle {
         let step = par2.into_int();
         let par1 = &mut params[0].get_value_mut(stack);
         let r = par1.into_rectangle_mut();
-        r.top_left.x = r.top_left.x.saturating_sub(step);
-        r.top_left.y = r.top_left.y.saturating_sub(step);
-        r.bot_right.x = r.bot_right.x.saturating_add(step);
-        r.bot_right.y = r.bot_right.y.saturating_add(step);
+        r.top_left.set(r.top_left.x.saturating_sub(step), r.top_left.y.saturating_sub(step));
+        r.bot_right.set(r.bot_right.x.saturating_add(step), r.bot_right.y.saturating_add(step));
         None
     }
 
@@ -439,10 +611,8 @@ pub mod rectangle {
         let par2 = &params[1].get_value(stack);
         let diff = par2.into_pos();
         let r = params[0].get_value_mut(stack).into_rectangle_mut();
-        r.top_left.x = r.top_left.x.saturating_add(diff.x);
-        r.top_left.y = r.top_left.y.saturating_add(diff.y);
-        r.bot_right.x = r.bot_right.x.saturating_add(diff.x);
-        r.bot_right.y = r.bot_right.y.saturating_add(diff.y);
+        r.top_left.set(r.top_left.x.saturating_add(diff.x), r.top_left.y.saturating_add(diff.y));
+        r.bot_right.set(r.bot_right.x.saturating_add(diff.x), r.bot_right.y.saturating_add(diff.y));
         None
     }
 }
@@ -512,3 +682,1428 @@ pub mod row {
         None
     }
 }
+
+pub mod float {
+    use super::*;
+
+    pub fn round(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("round", params, 1);
+        let f = params[0].get_value(stack).into_float();
+        Some(VariableValue::Int(f.round() as i32))
+    }
+
+    pub fn floor(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("floor", params, 1);
+        let f = params[0].get_value(stack).into_float();
+        Some(VariableValue::Int(f.floor() as i32))
+    }
+
+    /// Convert Float to Int, the fractional part is truncated.
+    pub fn as_int(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("as Int", params, 1);
+        let f = params[0].get_value(stack).into_float();
+        Some(VariableValue::Int(f as i32))
+    }
+
+    pub fn from_int(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("as Float", params, 1);
+        let i = params[0].get_value(stack).into_int();
+        Some(VariableValue::Float(i as f64))
+    }
+
+    pub fn add_to(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("add", params, 2);
+        let f1 = params[0].get_value(stack).into_float();
+        let f2 = params[1].get_value(stack).into_float();
+        params[1].set_value(stack, VariableValue::Float(f2 + f1));
+        None
+    }
+
+    pub fn sub(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("sub", params, 2);
+        let f1 = params[0].get_value(stack).into_float();
+        let f2 = params[1].get_value(stack).into_float();
+        params[1].set_value(stack, VariableValue::Float(f2 - f1));
+        None
+    }
+
+    pub fn multiply(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("multiply", params, 2);
+        let f1 = params[0].get_value(stack).into_float();
+        let f2 = params[1].get_value(stack).into_float();
+        params[0].set_value(stack, VariableValue::Float(f1 * f2));
+        None
+    }
+
+    pub fn divide(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("divide", params, 2);
+        let f1 = params[0].get_value(stack).into_float();
+        let f2 = params[1].get_value(stack).into_float();
+        if f2 == 0.0 {
+            panic!("error: division by zero") // TODO: friendlify
+        }
+        params[0].set_value(stack, VariableValue::Float(f1 / f2));
+        None
+    }
+
+    /// Move a position by a fractional offset, the sub-pixel part is kept in the position.
+    pub fn move_by(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("move by", params, 3);
+        let dx = params[1].get_value(stack).into_float();
+        let dy = params[2].get_value(stack).into_float();
+        let mut pos = params[0].get_value(stack).into_pos();
+        pos.translate(dx, dy);
+        params[0].set_value(stack, VariableValue::Pos(pos));
+        None
+    }
+}
+
+pub mod logic {
+    use super::*;
+
+    pub fn truth(_context: &mut Context, _stack: &mut Stack, _params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        Some(VariableValue::Bool(true))
+    }
+
+    pub fn falsity(_context: &mut Context, _stack: &mut Stack, _params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        Some(VariableValue::Bool(false))
+    }
+
+    pub fn not(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("not", params, 1);
+        let b = params[0].get_value(stack).into_bool();
+        Some(VariableValue::Bool(!b))
+    }
+
+    pub fn and(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("and", params, 2);
+        let b1 = params[0].get_value(stack).into_bool();
+        let b2 = params[1].get_value(stack).into_bool();
+        Some(VariableValue::Bool(b1 && b2))
+    }
+
+    pub fn or(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("or", params, 2);
+        let b1 = params[0].get_value(stack).into_bool();
+        let b2 = params[1].get_value(stack).into_bool();
+        Some(VariableValue::Bool(b1 || b2))
+    }
+
+    pub fn is_greater(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("is greater than", params, 2);
+        let i1 = params[0].get_value(stack).into_int();
+        let i2 = params[1].get_value(stack).into_int();
+        Some(VariableValue::Bool(i1 > i2))
+    }
+
+    pub fn is_less(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("is less than", params, 2);
+        let i1 = params[0].get_value(stack).into_int();
+        let i2 = params[1].get_value(stack).into_int();
+        Some(VariableValue::Bool(i1 < i2))
+    }
+
+    pub fn equals(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("equals", params, 2);
+        let v1 = params[0].get_value(stack);
+        let v2 = params[1].get_value(stack);
+        Some(VariableValue::Bool(v1 == v2))
+    }
+
+    pub fn is_inside(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("is inside", params, 2);
+        let pos = params[0].get_value(stack).into_pos();
+        let r = params[1].get_value(stack).into_rectangle();
+        Some(VariableValue::Bool(r.contains(&pos)))
+    }
+
+    pub fn overlaps(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("overlaps", params, 2);
+        let r1 = params[0].get_value(stack).into_rectangle();
+        let r2 = params[1].get_value(stack).into_rectangle();
+        Some(VariableValue::Bool(r1.overlaps(&r2)))
+    }
+
+    /// Activate the action while the rectangles overlap, e.g. a onetime action (`!at 0 frames`)
+    /// runs once per hit.
+    pub fn activate_on_overlap(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("activate when rectangle hits rectangle", params, 3);
+        let label = params[0].get_value(stack).into_string();
+        let r1 = params[1].get_value(stack).into_rectangle();
+        let r2 = params[2].get_value(stack).into_rectangle();
+        if r1.overlaps(&r2) {
+            action_handles.push(ActionHandle::Enable(label.to_string()));
+        }
+        None
+    }
+
+    pub fn activate_on_inside(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("activate when position hits rectangle", params, 3);
+        let label = params[0].get_value(stack).into_string();
+        let pos = params[1].get_value(stack).into_pos();
+        let r = params[2].get_value(stack).into_rectangle();
+        if r.contains(&pos) {
+            action_handles.push(ActionHandle::Enable(label.to_string()));
+        }
+        None
+    }
+
+    pub fn activate_if(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("activate if", params, 2);
+        let label = params[0].get_value(stack).into_string();
+        if params[1].get_value(stack).into_bool() {
+            action_handles.push(ActionHandle::Enable(label.to_string()));
+        }
+        None
+    }
+
+    pub fn deactivate_if(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("deactivate if", params, 2);
+        let label = params[0].get_value(stack).into_string();
+        if params[1].get_value(stack).into_bool() {
+            action_handles.push(ActionHandle::Disable(label.to_string()));
+        }
+        None
+    }
+
+    pub fn stop_if(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("stop if", params, 1);
+        if params[0].get_value(stack).into_bool() {
+            action_handles.push(ActionHandle::Stop);
+        }
+        None
+    }
+}
+
+pub mod color {
+    use crate::variable::color;
+
+    use super::*;
+
+    /// Mix two colors, the Int is the percentage of the second color.
+    pub fn mix(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("mix", params, 3);
+        let c1 = params[0].get_value(stack).into_color();
+        let c2 = params[1].get_value(stack).into_color();
+        let percent = params[2].get_value(stack).into_int();
+        Some(VariableValue::Color(color::mix(c1, c2, percent as f64 / 100.0)))
+    }
+
+    pub fn lighten(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("lighten", params, 2);
+        let c = params[0].get_value(stack).into_color();
+        let percent = params[1].get_value(stack).into_int();
+        Some(VariableValue::Color(color::mix(c, [255,255,255].into(), percent as f64 / 100.0)))
+    }
+
+    pub fn darken(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("darken", params, 2);
+        let c = params[0].get_value(stack).into_color();
+        let percent = params[1].get_value(stack).into_int();
+        Some(VariableValue::Color(color::mix(c, [0,0,0].into(), percent as f64 / 100.0)))
+    }
+
+    /// Hue is in degrees, saturation and value are percentages.
+    pub fn from_hsv(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("color with hue", params, 3);
+        let h = params[0].get_value(stack).into_int();
+        let s = params[1].get_value(stack).into_int();
+        let v = params[2].get_value(stack).into_int();
+        Some(VariableValue::Color(color::from_hsv(h as f64, s as f64 / 100.0, v as f64 / 100.0)))
+    }
+}
+
+pub mod frame {
+    use crate::variable::Rectangle;
+
+    use super::*;
+
+    pub fn width(context: &mut Context, _stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("frame width", params, 0);
+        Some(VariableValue::Int(context.get_width() as i32))
+    }
+
+    pub fn height(context: &mut Context, _stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("frame height", params, 0);
+        Some(VariableValue::Int(context.get_height() as i32))
+    }
+
+    pub fn center(context: &mut Context, _stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("frame center", params, 0);
+        let x = context.get_width() as i32 / 2;
+        let y = context.get_height() as i32 / 2;
+        Some(VariableValue::Pos(Position::new(x, y)))
+    }
+
+    pub fn rectangle(context: &mut Context, _stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("frame rectangle", params, 0);
+        let bot_right = Position::new(context.get_width() as i32, context.get_height() as i32);
+        Some(VariableValue::Rectangle(Rectangle::new(Position::default(), bot_right)))
+    }
+
+    /// Index of the current frame in the input, the first frame has index 1 (same as `at 1 frame`,
+    /// unless the speed of the video was changed).
+    pub fn current(context: &mut Context, _stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("current frame", params, 0);
+        Some(VariableValue::Int(context.get_frame_index() as i32))
+    }
+
+    pub fn time(context: &mut Context, _stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("current time", params, 0);
+        Some(VariableValue::Int(context.get_time_ms() as i32))
+    }
+
+    pub fn fps(context: &mut Context, _stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("video fps", params, 0);
+        Some(VariableValue::Float(context.get_fps()))
+    }
+
+    pub fn count(context: &mut Context, _stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("total frames", params, 0);
+        Some(VariableValue::Int(context.get_frame_count() as i32))
+    }
+}
+
+pub mod sample {
+    use crate::variable::{Color, Rectangle, color};
+    use crate::video::Samplable;
+
+    use super::*;
+
+    fn expect_frame<'a>(context: &'a Context, op_name: &str) -> &'a Frame {
+        if context.is_empty() {
+            panic!("error: {op_name} can only be used while processing frames"); // TODO: friendlify
+        }
+        context.get_current_frame()
+    }
+
+    fn corners(r: &Rectangle) -> ((i32,i32),(i32,i32)) {
+        ((r.top_left.x, r.top_left.y), (r.bot_right.x, r.bot_right.y))
+    }
+
+    pub fn color_at(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("color at", params, 1);
+        let pos = params[0].get_value(stack).into_pos();
+        let frame = expect_frame(context, "color at");
+        Some(VariableValue::Color(frame.color_at(pos.x, pos.y)))
+    }
+
+    pub fn color_at_image(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("color at of image", params, 2);
+        let pos = params[0].get_value(stack).into_pos();
+        let img = params[1].get_value(stack).into_image();
+        if img.width() == 0 || img.height() == 0 {
+            panic!("error: cannot take color of an empty image"); // TODO: friendlify
+        }
+        Some(VariableValue::Color(img.color_at(pos.x, pos.y)))
+    }
+
+    pub fn average_color(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("average color of", params, 1);
+        let (tl, br) = corners(&params[0].get_value(stack).into_rectangle());
+        let frame = expect_frame(context, "average color of");
+        Some(VariableValue::Color(frame.average_color(tl, br)))
+    }
+
+    pub fn average_color_image(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("average color of image", params, 1);
+        let img = params[0].get_value(stack).into_image();
+        let br = (img.width() as i32, img.height() as i32);
+        Some(VariableValue::Color(img.average_color((0,0), br)))
+    }
+
+    /// Average brightness of the rectangle (0-255).
+    pub fn brightness(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("brightness of", params, 1);
+        let (tl, br) = corners(&params[0].get_value(stack).into_rectangle());
+        let frame = expect_frame(context, "brightness of");
+        let bins = frame.histogram(tl, br);
+        let count: u64 = bins.iter().map(|b| *b as u64).sum();
+        if count == 0 {
+            return Some(VariableValue::Int(0));
+        }
+        let sum: u64 = bins.iter().enumerate().map(|(i, b)| i as u64 * *b as u64).sum();
+        Some(VariableValue::Int((sum as f64 / count as f64).round() as i32))
+    }
+
+    /// Histogram of brightness in the rectangle; 256 bins, one for each brightness level.
+    pub fn histogram(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("histogram of", params, 1);
+        let (tl, br) = corners(&params[0].get_value(stack).into_rectangle());
+        let frame = expect_frame(context, "histogram of");
+        let bins = frame.histogram(tl, br)
+            .iter()
+            .map(|b| VariableValue::Int(*b as i32).to_var())
+            .collect();
+        Some(VariableValue::Vec(bins))
+    }
+
+    /// Black or white, whichever is more readable on the given color.
+    pub fn contrasting(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("contrasting color for", params, 1);
+        let c = params[0].get_value(stack).into_color();
+        let out = if color::luminance(c) > 127.5 {
+            Color::from([0,0,0])
+        } else {
+            Color::from([255,255,255])
+        };
+        Some(VariableValue::Color(out))
+    }
+}
+
+pub mod shape {
+    use crate::variable::Position;
+    use crate::video::{Canvas, Drawable, Fill};
+    use crate::video::raster::{Point, Shape};
+
+    use super::*;
+
+    /// Color, gradient or effect to fill the shape with.
+    pub(super) fn fill_of(v: &VariableValue) -> Fill {
+        match v {
+            VariableValue::Color(c) => Fill::Color(*c),
+            VariableValue::Effect(e) => Fill::Effect(*e),
+            VariableValue::Gradient(g) => Fill::Gradient(*g),
+            _ => panic!("error: expected Color, Gradient or Effect, got {}", v.get_type()), // TODO: friendlify
+        }
+    }
+
+    /// Center of the pixel at the position (shapes given by points go through pixel centers).
+    pub(super) fn center(p: Position) -> Point {
+        let (x, y) = p.exact();
+        (x + 0.5, y + 0.5)
+    }
+
+    /// Top-left corner of the pixel at the position (rectangles span from corner to corner).
+    pub(super) fn corner(p: Position) -> Point {
+        p.exact()
+    }
+
+    pub(super) fn points(v: &VariableValue, stack: &Stack) -> Vec<Point> {
+        v.into_vec().iter().map(|p| center(p.get_value(stack).into_pos())).collect()
+    }
+
+    /// Widths and radii given by variables can be negative, those draw nothing (literals are checked when translating).
+    pub(super) fn length(n: i32) -> f64 {
+        n.max(0) as f64
+    }
+
+    /// Paint the whole frame with a color or a gradient.
+    pub fn fill_frame(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("fill frame with", params, 1);
+        if context.is_empty() { return None; }
+        let fill = fill_of(params[0].get_value(stack));
+        let mut frame = context.get_target_mut();
+        let (width, height) = frame.size();
+        match fill {
+            Fill::Color(c) => frame.fill_rect((0, 0), (width as usize, height as usize), c),
+            Fill::Gradient(g) => for y in 0..height {
+                for x in 0..width {
+                    frame.set_color(x, y, g.color_at(x as f64, y as f64));
+                }
+            },
+            Fill::Effect(_) => panic!("error: fill frame expects a Color or a Gradient"), // TODO: friendlify
+        }
+        None
+    }
+
+    fn draw(context: &mut Context, shape: Shape, fill: Fill) {
+        let antialias = context.antialias();
+        context.get_target_mut().draw_shape(&shape, fill, antialias);
+    }
+
+    /// Draw a rectangle, if it lies on sub-pixel positions (and anti-aliasing is enabled)
+    /// or it is filled with a gradient, it is rasterized like other shapes.
+    pub(super) fn rect(context: &mut Context, top_left: Position, bot_right: Position, fill: Fill, outline: bool) {
+        let sub_pixel = context.antialias() && (top_left.has_fract() || bot_right.has_fract());
+        if !sub_pixel && !matches!(fill, Fill::Gradient(_)) {
+            let tl = (top_left.x as usize, top_left.y as usize);
+            let br = (bot_right.x as usize, bot_right.y as usize);
+            let mut frame = context.get_target_mut();
+            match (fill, outline) {
+                (Fill::Color(c), false) => frame.draw_rect(tl, br, c),
+                (Fill::Color(c), true) => frame.draw_rect_outline(tl, br, c),
+                (Fill::Effect(e), _) => frame.draw_effect_rect(tl, br, e),
+                (Fill::Gradient(_), _) => unreachable!(),
+            }
+            return;
+        }
+        let outline = if outline { Some(1.0) } else { None };
+        draw(context, Shape::RoundedRect { top_left: corner(top_left), bottom_right: corner(bot_right), radius: 0.0, outline }, fill);
+    }
+
+    pub fn line(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("draw line", params, 4);
+        if context.is_empty() { return None; }
+        let fill = fill_of(params[0].get_value(stack));
+        let from = center(params[1].get_value(stack).into_pos());
+        let to = center(params[2].get_value(stack).into_pos());
+        let width = length(params[3].get_value(stack).into_int());
+        draw(context, Shape::Line { from, to, width }, fill);
+        None
+    }
+
+    pub fn circle(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("draw circle", params, 3);
+        if context.is_empty() { return None; }
+        let fill = fill_of(params[0].get_value(stack));
+        let c = center(params[1].get_value(stack).into_pos());
+        let r = length(params[2].get_value(stack).into_int());
+        draw(context, Shape::Ellipse { center: c, radii: (r, r), outline: None }, fill);
+        None
+    }
+
+    pub fn circle_outline(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("draw circle outline", params, 4);
+        if context.is_empty() { return None; }
+        let fill = fill_of(params[0].get_value(stack));
+        let c = center(params[1].get_value(stack).into_pos());
+        let r = length(params[2].get_value(stack).into_int());
+        let w = length(params[3].get_value(stack).into_int());
+        draw(context, Shape::Ellipse { center: c, radii: (r, r), outline: Some(w) }, fill);
+        None
+    }
+
+    pub fn ellipse(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("draw ellipse", params, 4);
+        if context.is_empty() { return None; }
+        let fill = fill_of(params[0].get_value(stack));
+        let c = center(params[1].get_value(stack).into_pos());
+        let rx = length(params[2].get_value(stack).into_int());
+        let ry = length(params[3].get_value(stack).into_int());
+        draw(context, Shape::Ellipse { center: c, radii: (rx, ry), outline: None }, fill);
+        None
+    }
+
+    pub fn ellipse_outline(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("draw ellipse outline", params, 5);
+        if context.is_empty() { return None; }
+        let fill = fill_of(params[0].get_value(stack));
+        let c = center(params[1].get_value(stack).into_pos());
+        let rx = length(params[2].get_value(stack).into_int());
+        let ry = length(params[3].get_value(stack).into_int());
+        let w = length(params[4].get_value(stack).into_int());
+        draw(context, Shape::Ellipse { center: c, radii: (rx, ry), outline: Some(w) }, fill);
+        None
+    }
+
+    pub fn polygon(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("draw polygon", params, 2);
+        if context.is_empty() { return None; }
+        let fill = fill_of(params[0].get_value(stack));
+        let points = points(params[1].get_value(stack), stack);
+        draw(context, Shape::Polygon(points), fill);
+        None
+    }
+
+    pub fn polygon_outline(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("draw polygon outline", params, 3);
+        if context.is_empty() { return None; }
+        let fill = fill_of(params[0].get_value(stack));
+        let points = points(params[1].get_value(stack), stack);
+        let width = length(params[2].get_value(stack).into_int());
+        draw(context, Shape::Polyline { points, width, closed: true }, fill);
+        None
+    }
+
+    pub fn polyline(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("draw polyline", params, 3);
+        if context.is_empty() { return None; }
+        let fill = fill_of(params[0].get_value(stack));
+        let points = points(params[1].get_value(stack), stack);
+        let width = length(params[2].get_value(stack).into_int());
+        draw(context, Shape::Polyline { points, width, closed: false }, fill);
+        None
+    }
+
+    pub fn rounded_rect(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("draw rounded rectangle", params, 4);
+        if context.is_empty() { return None; }
+        let fill = fill_of(params[0].get_value(stack));
+        let top_left = corner(params[1].get_value(stack).into_pos());
+        let bottom_right = corner(params[2].get_value(stack).into_pos());
+        let radius = length(params[3].get_value(stack).into_int());
+        draw(context, Shape::RoundedRect { top_left, bottom_right, radius, outline: None }, fill);
+        None
+    }
+
+    pub fn rounded_rect_outline(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("draw rounded rectangle outline", params, 5);
+        if context.is_empty() { return None; }
+        let fill = fill_of(params[0].get_value(stack));
+        let top_left = corner(params[1].get_value(stack).into_pos());
+        let bottom_right = corner(params[2].get_value(stack).into_pos());
+        let radius = length(params[3].get_value(stack).into_int());
+        let w = length(params[4].get_value(stack).into_int());
+        draw(context, Shape::RoundedRect { top_left, bottom_right, radius, outline: Some(w) }, fill);
+        None
+    }
+}
+
+pub mod timing {
+    use super::*;
+
+    fn set_speed(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, blend: bool) {
+        expect_param_count("set speed to", params, 1);
+        let speed = params[0].get_value(stack).into_float();
+        if speed <= 0.0 {
+            panic!("error: set speed to: speed has to be positive, got {speed} (use reverse to play backwards)"); // TODO: friendlify
+        }
+        context.set_speed(speed, blend);
+    }
+
+    /// Following frames are taken from the input at a different speed, frames are dropped or repeated.
+    pub fn speed(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        set_speed(context, stack, params, false);
+        None
+    }
+
+    /// Like `speed`, frames are blended instead (smoother slow motion, motion blur when fast).
+    pub fn speed_blended(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        set_speed(context, stack, params, true);
+        None
+    }
+
+    pub fn freeze(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("freeze for", params, 1);
+        let frames = params[0].get_value(stack).into_int();
+        if frames < 0 {
+            panic!("error: freeze for: cannot freeze for negative number of frames {frames}"); // TODO: friendlify
+        }
+        context.freeze(frames as usize);
+        None
+    }
+
+    /// Play a segment of the input backwards, the segment has to be after the current frame.
+    pub fn reverse(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("reverse from to", params, 2);
+        let from = params[0].get_value(stack).into_int();
+        let to = params[1].get_value(stack).into_int();
+        let current = context.get_frame_index() as i32;
+        if from <= current {
+            panic!("error: reverse from {from} to {to}: only frames after the current frame ({current}) can be reversed"); // TODO: friendlify
+        }
+        if to < from {
+            panic!("error: reverse from {from} to {to}: the segment ends before it starts"); // TODO: friendlify
+        }
+        context.reverse(from as usize, to as usize);
+        None
+    }
+}
+
+pub mod transition {
+    use crate::variable::Transition;
+    use crate::video::{Canvas, Transformable};
+    use crate::video::transition::RunningTransition;
+
+    use super::*;
+
+    pub fn crossfade(_context: &mut Context, _stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("crossfade", params, 0);
+        Some(VariableValue::Transition(Transition::Crossfade))
+    }
+
+    pub fn dip(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("dip to", params, 1);
+        let c = params[0].get_value(stack).into_color();
+        Some(VariableValue::Transition(Transition::Dip(c)))
+    }
+
+    pub fn wipe(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("wipe", params, 1);
+        let d = params[0].get_value(stack).into_direction();
+        Some(VariableValue::Transition(Transition::Wipe(d)))
+    }
+
+    pub fn slide(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("slide", params, 1);
+        let d = params[0].get_value(stack).into_direction();
+        Some(VariableValue::Transition(Transition::Slide(d)))
+    }
+
+    pub fn zoom(_context: &mut Context, _stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("zoom in", params, 0);
+        Some(VariableValue::Transition(Transition::Zoom))
+    }
+
+    /// Start a transition between the output and a still copy of the image taken now, the image is
+    /// stretched over the frame (transparent parts are black). Changes to the image later on do not show.
+    fn start(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, to_image: bool) {
+        let op_name = if to_image { "transition to" } else { "transition from" };
+        expect_param_count(op_name, params, 3);
+        if context.is_empty() { return; }
+        let transition = params[1].get_value(stack).into_transition();
+        let duration = params[2].get_value(stack).into_int();
+        if duration <= 0 {
+            panic!("error: {op_name}: duration has to be positive, got {duration}"); // TODO: friendlify
+        }
+        let (width, height) = context.get_current_frame().dimensions();
+        let mut img = params[0].get_value(stack).into_image().clone();
+        img.scale_to(width, height);
+        let mut picture = Frame::new(width, height);
+        picture.draw_image(&img, 0, 0);
+        context.start_transition(RunningTransition::new(transition, picture, to_image, duration as usize));
+    }
+
+    /// The output turns into the still image over the given number of frames (starting with the current one).
+    pub fn to(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        start(context, stack, params, true);
+        None
+    }
+
+    /// The still image turns into the output over the given number of frames (starting with the current one).
+    pub fn from(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        start(context, stack, params, false);
+        None
+    }
+}
+
+pub mod history {
+    use super::*;
+
+    /// Variables cannot look into the future, those get the current frame (literals are checked when translating).
+    fn frames_ago(ago: i32) -> usize {
+        ago.max(0) as usize
+    }
+
+    /// The frame as it was written `Int` frames ago.
+    pub fn frame_ago(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("frame ago", params, 1);
+        if context.is_empty() {
+            panic!("error: frame ago can only be used while processing frames"); // TODO: friendlify
+        }
+        let ago = frames_ago(params[0].get_value(stack).into_int());
+        if ago == 0 {
+            return Some(VariableValue::Image(to_image(context.get_current_frame())));
+        }
+        Some(VariableValue::Image(to_image(context.get_past_frame(ago))))
+    }
+
+    /// The frame as it was read from the input `Int` frames ago.
+    pub fn original_frame_ago(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("original frame ago", params, 1);
+        if context.is_empty() {
+            panic!("error: original frame ago can only be used while processing frames"); // TODO: friendlify
+        }
+        let ago = frames_ago(params[0].get_value(stack).into_int());
+        Some(VariableValue::Image(to_image(context.get_original_frame(ago))))
+    }
+}
+
+pub mod sprite {
+    use crate::variable::{Edges, Look, Sprite};
+
+    use super::*;
+
+    pub fn of_image(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("sprite of image", params, 2);
+        let img = params[0].get_value(stack).into_image().clone();
+        let pos = params[1].get_value(stack).into_pos();
+        Some(VariableValue::Sprite(Sprite::new(Look::Image(img), pos)))
+    }
+
+    /// Negative sizes of variables make empty sprites (literals are checked when translating).
+    fn size(v: i32) -> u32 {
+        v.max(0) as u32
+    }
+
+    pub fn of_rectangle(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("sprite of rectangle", params, 4);
+        let c = params[0].get_value(stack).into_color();
+        let width = size(params[1].get_value(stack).into_int());
+        let height = size(params[2].get_value(stack).into_int());
+        let pos = params[3].get_value(stack).into_pos();
+        Some(VariableValue::Sprite(Sprite::new(Look::Rectangle(c, width, height), pos)))
+    }
+
+    pub fn of_circle(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("sprite of circle", params, 3);
+        let c = params[0].get_value(stack).into_color();
+        let radius = size(params[1].get_value(stack).into_int());
+        let pos = params[2].get_value(stack).into_pos();
+        Some(VariableValue::Sprite(Sprite::new(Look::Circle(c, radius), pos)))
+    }
+
+    pub fn move_by(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("move sprite by", params, 2);
+        let (dx, dy) = params[1].get_value(stack).into_pos().exact();
+        params[0].get_value_mut(stack).into_sprite_mut().position.translate(dx, dy);
+        None
+    }
+
+    pub fn move_to(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("move sprite to", params, 2);
+        let pos = params[1].get_value(stack).into_pos();
+        params[0].get_value_mut(stack).into_sprite_mut().position = pos;
+        None
+    }
+
+    /// The sprite moves by the velocity every frame.
+    pub fn set_velocity(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("set velocity of sprite", params, 2);
+        let velocity = params[1].get_value(stack).into_pos().exact();
+        params[0].get_value_mut(stack).into_sprite_mut().velocity = velocity;
+        None
+    }
+
+    fn set_edges(operation: &str, stack: &mut Stack, params: &mut Vec<Variable>, edges: Edges) {
+        expect_param_count(operation, params, 1);
+        params[0].get_value_mut(stack).into_sprite_mut().edges = edges;
+    }
+
+    /// From now on the sprite bounces off the edges of the frame.
+    pub fn bounce(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        set_edges("bounce sprite off edges", stack, params, Edges::Bounce);
+        None
+    }
+
+    /// From now on the sprite stops at the edges of the frame.
+    pub fn clamp(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        set_edges("clamp sprite to edges", stack, params, Edges::Clamp);
+        None
+    }
+
+    /// From now on the sprite comes back from the opposite edge when it leaves the frame.
+    pub fn wrap(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        set_edges("wrap sprite around edges", stack, params, Edges::Wrap);
+        None
+    }
+
+    /// Hidden sprites do not hit anything.
+    fn hit(a: &Sprite, b: &Sprite) -> bool {
+        a.visible && b.visible && a.rectangle().overlaps(&b.rectangle())
+    }
+
+    pub fn hits(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("sprite hits sprite", params, 2);
+        let a = params[0].get_value(stack).into_sprite();
+        let b = params[1].get_value(stack).into_sprite();
+        Some(VariableValue::Bool(hit(a, b)))
+    }
+
+    pub fn activate_on_hit(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("activate when sprite hits sprite", params, 3);
+        let label = params[0].get_value(stack).into_string();
+        if hit(params[1].get_value(stack).into_sprite(), params[2].get_value(stack).into_sprite()) {
+            action_handles.push(ActionHandle::Enable(label.to_string()));
+        }
+        None
+    }
+
+    pub fn hide(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("hide sprite", params, 1);
+        params[0].get_value_mut(stack).into_sprite_mut().visible = false;
+        None
+    }
+
+    pub fn show(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("show sprite", params, 1);
+        params[0].get_value_mut(stack).into_sprite_mut().visible = true;
+        None
+    }
+
+    pub fn rotate(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("rotate sprite by", params, 2);
+        let degrees = params[1].get_value(stack).into_int();
+        let s = params[0].get_value_mut(stack).into_sprite_mut();
+        s.rotation = (s.rotation + degrees).rem_euclid(360);
+        None
+    }
+
+    pub fn scale(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("scale sprite by", params, 2);
+        let factor = params[1].get_value(stack).into_float().max(0.0);
+        params[0].get_value_mut(stack).into_sprite_mut().scale *= factor;
+        None
+    }
+
+    /// Opacity of the sprite in percent.
+    pub fn set_opacity(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("set opacity of sprite", params, 2);
+        let opacity = params[1].get_value(stack).into_int().clamp(0, 100) as f64 / 100.0;
+        params[0].get_value_mut(stack).into_sprite_mut().opacity = opacity;
+        None
+    }
+
+    /// Draw the sprite on the layer, so that it is ordered with the rest of the layer.
+    pub fn put_on_layer(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("put sprite on layer", params, 2);
+        let name = params[1].get_value(stack).into_string().to_string();
+        params[0].get_value_mut(stack).into_sprite_mut().layer = Some(name);
+        None
+    }
+
+    pub fn position(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("position of sprite", params, 1);
+        Some(VariableValue::Pos(params[0].get_value(stack).into_sprite().position))
+    }
+
+    pub fn rectangle(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("rectangle of sprite", params, 1);
+        Some(VariableValue::Rectangle(params[0].get_value(stack).into_sprite().rectangle()))
+    }
+}
+
+pub mod emitter {
+    use crate::variable::Emitter;
+
+    use super::*;
+
+    /// Negative rates, lifetimes and sizes of variables count as 0 (literals are checked when translating).
+    fn non_negative(v: i32) -> u32 {
+        v.max(0) as u32
+    }
+
+    pub fn new(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("emitter at", params, 2);
+        let pos = params[0].get_value(stack).into_pos();
+        let rate = non_negative(params[1].get_value(stack).into_int());
+        Some(VariableValue::Emitter(Emitter::new(pos, rate)))
+    }
+
+    pub fn move_to(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("move emitter to", params, 2);
+        let pos = params[1].get_value(stack).into_pos();
+        params[0].get_value_mut(stack).into_emitter_mut().position = pos;
+        None
+    }
+
+    /// Particles appear anywhere in the rectangle (e.g. snow from the top edge of the frame).
+    pub fn set_area(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("set area of emitter", params, 2);
+        let r = params[1].get_value(stack).into_rectangle();
+        params[0].get_value_mut(stack).into_emitter_mut().area = Some(r);
+        None
+    }
+
+    pub fn set_rate(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("set rate of emitter", params, 2);
+        let rate = non_negative(params[1].get_value(stack).into_int());
+        params[0].get_value_mut(stack).into_emitter_mut().rate = rate;
+        None
+    }
+
+    pub fn set_lifetime(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("set lifetime of emitter", params, 2);
+        let lifetime = non_negative(params[1].get_value(stack).into_int());
+        params[0].get_value_mut(stack).into_emitter_mut().lifetime = lifetime;
+        None
+    }
+
+    pub fn set_velocity(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("set velocity of emitter", params, 2);
+        let velocity = params[1].get_value(stack).into_pos().exact();
+        params[0].get_value_mut(stack).into_emitter_mut().velocity = velocity;
+        None
+    }
+
+    /// Maximal random change of the velocity of new particles on each axis.
+    pub fn set_spread(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("set spread of emitter", params, 2);
+        let spread = params[1].get_value(stack).into_float().abs();
+        params[0].get_value_mut(stack).into_emitter_mut().spread = spread;
+        None
+    }
+
+    pub fn set_gravity(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("set gravity of emitter", params, 2);
+        let gravity = params[1].get_value(stack).into_float();
+        params[0].get_value_mut(stack).into_emitter_mut().gravity = gravity;
+        None
+    }
+
+    pub fn set_color(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("set color of emitter", params, 2);
+        let c = params[1].get_value(stack).into_color();
+        params[0].get_value_mut(stack).into_emitter_mut().colors = (c, c);
+        None
+    }
+
+    /// Particles change their color over their life.
+    pub fn set_colors(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("set color of emitter from to", params, 3);
+        let from = params[1].get_value(stack).into_color();
+        let to = params[2].get_value(stack).into_color();
+        params[0].get_value_mut(stack).into_emitter_mut().colors = (from, to);
+        None
+    }
+
+    pub fn set_size(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("set size of emitter", params, 2);
+        let size = non_negative(params[1].get_value(stack).into_int()) as f64;
+        params[0].get_value_mut(stack).into_emitter_mut().sizes = (size, size);
+        None
+    }
+
+    /// Particles grow or shrink over their life.
+    pub fn set_sizes(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("set size of emitter from to", params, 3);
+        let from = non_negative(params[1].get_value(stack).into_int()) as f64;
+        let to = non_negative(params[2].get_value(stack).into_int()) as f64;
+        params[0].get_value_mut(stack).into_emitter_mut().sizes = (from, to);
+        None
+    }
+
+    /// The same seed gives the same particles in every render.
+    pub fn seed(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("seed emitter with", params, 2);
+        let seed = params[1].get_value(stack).into_int();
+        params[0].get_value_mut(stack).into_emitter_mut().seed(seed as u64);
+        None
+    }
+
+    /// No new particles are emitted, the existing ones live on.
+    pub fn pause(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("pause emitter", params, 1);
+        params[0].get_value_mut(stack).into_emitter_mut().emitting = false;
+        None
+    }
+
+    pub fn resume(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("resume emitter", params, 1);
+        params[0].get_value_mut(stack).into_emitter_mut().emitting = true;
+        None
+    }
+
+    /// Draw the particles on the layer, so that they are ordered with the rest of the layer.
+    pub fn put_on_layer(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("put emitter on layer", params, 2);
+        let name = params[1].get_value(stack).into_string().to_string();
+        params[0].get_value_mut(stack).into_emitter_mut().layer = Some(name);
+        None
+    }
+}
+
+pub mod subtitles {
+    use super::*;
+
+    /// Position of the bottom center of the subtitles.
+    pub fn set_position(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("set subtitle position to", params, 1);
+        let pos = params[0].get_value(stack).into_pos();
+        context.get_subtitle_style_mut().position = Some(pos);
+        None
+    }
+
+    /// Height of a line of the subtitles in pixels.
+    pub fn set_size(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("set subtitle size to", params, 1);
+        let size = params[0].get_value(stack).into_int();
+        if size <= 0 {
+            panic!("error: set subtitle size to: size has to be positive, got {size}"); // TODO: friendlify
+        }
+        context.get_subtitle_style_mut().size = Some(size as u32);
+        None
+    }
+
+    pub fn set_color(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("set subtitle color to", params, 1);
+        let c = params[0].get_value(stack).into_color();
+        context.get_subtitle_style_mut().color = c;
+        None
+    }
+}
+
+pub mod layer {
+    use super::*;
+    use crate::video::BlendMode;
+
+    pub fn set_z_index(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("set z index of layer", params, 2);
+        if context.is_empty() { return None; }
+        let name = params[0].get_value(stack).into_string();
+        let z_index = params[1].get_value(stack).into_int();
+        context.get_layer_mut(name).z_index = z_index;
+        None
+    }
+
+    /// Opacity of the whole layer in percent.
+    pub fn set_opacity(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("set opacity of layer", params, 2);
+        if context.is_empty() { return None; }
+        let name = params[0].get_value(stack).into_string();
+        let opacity = params[1].get_value(stack).into_int();
+        if !(0..=100).contains(&opacity) {
+            panic!("error: set opacity of layer: opacity has to be between 0 and 100 percent, got {opacity}"); // TODO: friendlify
+        }
+        context.get_layer_mut(name).opacity = opacity as f64 / 100.0;
+        None
+    }
+
+    pub fn set_blend_mode(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("set blend mode of layer", params, 2);
+        if context.is_empty() { return None; }
+        let name = params[0].get_value(stack).into_string();
+        let mode = params[1].get_value(stack).into_string();
+        let Some(mode) = BlendMode::from_name(mode) else {
+            panic!("error: set blend mode of layer: unknown blend mode \"{mode}\", expected one of normal, multiply, screen, overlay, add, difference"); // TODO: friendlify
+        };
+        context.get_layer_mut(name).blend_mode = mode;
+        None
+    }
+}
+
+pub mod mask {
+    use crate::variable::Mask;
+    use crate::video::{Canvas, Drawable};
+    use crate::video::raster::Shape;
+
+    use super::*;
+    use super::shape::{center, corner, fill_of, length, points};
+
+    fn of_shape(context: &Context, shape: Shape) -> Option<VariableValue> {
+        Some(VariableValue::Mask(Mask::from_shape(&shape, context.antialias(), context.frame_size())))
+    }
+
+    pub fn circle(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("circle mask", params, 2);
+        let c = center(params[0].get_value(stack).into_pos());
+        let r = length(params[1].get_value(stack).into_int());
+        of_shape(context, Shape::Ellipse { center: c, radii: (r, r), outline: None })
+    }
+
+    pub fn ellipse(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("ellipse mask", params, 3);
+        let c = center(params[0].get_value(stack).into_pos());
+        let rx = length(params[1].get_value(stack).into_int());
+        let ry = length(params[2].get_value(stack).into_int());
+        of_shape(context, Shape::Ellipse { center: c, radii: (rx, ry), outline: None })
+    }
+
+    pub fn polygon(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("polygon mask", params, 1);
+        let points = points(params[0].get_value(stack), stack);
+        of_shape(context, Shape::Polygon(points))
+    }
+
+    pub fn rectangle(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("mask of rectangle", params, 1);
+        let r = params[0].get_value(stack).into_rectangle();
+        of_shape(context, Shape::RoundedRect { top_left: corner(r.top_left), bottom_right: corner(r.bot_right), radius: 0.0, outline: None })
+    }
+
+    pub fn rounded_rect(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("rounded rectangle mask", params, 3);
+        let top_left = corner(params[0].get_value(stack).into_pos());
+        let bottom_right = corner(params[1].get_value(stack).into_pos());
+        let radius = length(params[2].get_value(stack).into_int());
+        of_shape(context, Shape::RoundedRect { top_left, bottom_right, radius, outline: None })
+    }
+
+    /// Mask from the brightness of the image placed at the position.
+    pub fn luminance(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("luminance mask", params, 2);
+        let img = params[0].get_value(stack).into_image();
+        let pos = params[1].get_value(stack).into_pos();
+        Some(VariableValue::Mask(Mask::from_luminance(img, (pos.x, pos.y))))
+    }
+
+    /// Mask from the transparency of the image placed at the position.
+    pub fn alpha(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("alpha mask", params, 2);
+        let img = params[0].get_value(stack).into_image();
+        let pos = params[1].get_value(stack).into_pos();
+        Some(VariableValue::Mask(Mask::from_alpha(img, (pos.x, pos.y))))
+    }
+
+    pub fn invert(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("invert mask", params, 1);
+        params[0].get_value_mut(stack).into_mask_mut().invert();
+        None
+    }
+
+    pub fn feather(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("feather mask", params, 2);
+        let radius = length(params[1].get_value(stack).into_int());
+        params[0].get_value_mut(stack).into_mask_mut().feather(radius as u32);
+        None
+    }
+
+    /// Draw a color, gradient or effect over the frame, limited by the mask.
+    pub fn draw_through(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("draw through mask", params, 2);
+        if context.is_empty() { return None; }
+        let fill = fill_of(params[0].get_value(stack));
+        let mask = params[1].get_value(stack).into_mask();
+        context.get_target_mut().draw_through(fill, mask);
+        None
+    }
+
+    pub fn draw_image_through(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("draw image at through mask", params, 3);
+        if context.is_empty() { return None; }
+        let img = params[0].get_value(stack).into_image();
+        let pos = params[1].get_value(stack).into_pos();
+        let mask = params[2].get_value(stack).into_mask();
+        context.get_target_mut().draw_image_through(img, pos.x.into(), pos.y.into(), mask);
+        None
+    }
+}
+
+pub mod gradient {
+    use crate::variable::Gradient;
+
+    use super::*;
+
+    pub fn linear(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("gradient", params, 4);
+        let from = params[0].get_value(stack).into_color();
+        let start = params[1].get_value(stack).into_pos();
+        let to = params[2].get_value(stack).into_color();
+        let end = params[3].get_value(stack).into_pos();
+        Some(VariableValue::Gradient(Gradient::Linear { from, start, to, end }))
+    }
+
+    pub fn radial(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("radial gradient", params, 4);
+        let from = params[0].get_value(stack).into_color();
+        let center = params[1].get_value(stack).into_pos();
+        let to = params[2].get_value(stack).into_color();
+        let radius = super::shape::length(params[3].get_value(stack).into_int());
+        Some(VariableValue::Gradient(Gradient::Radial { from, center, to, radius }))
+    }
+}
+
+pub mod camera {
+    use crate::variable::Rectangle;
+    use crate::video::CameraTransform;
+
+    use super::*;
+
+    fn corners(r: &Rectangle) -> (f64, f64, f64, f64) {
+        let (l, t) = r.top_left.exact();
+        let (r, b) = r.bot_right.exact();
+        (l, t, r, b)
+    }
+
+    pub fn zoom_to(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("zoom to", params, 1);
+        if context.is_empty() { return None; }
+        let (l, t, r, b) = corners(&params[0].get_value(stack).into_rectangle());
+        if l == r || t == b {
+            panic!("error: cannot zoom to an empty rectangle"); // TODO: friendlify
+        }
+        context.push_camera_transform(CameraTransform::Zoom(l, t, r, b));
+        None
+    }
+
+    /// Zoom around the center of the frame, factors above 1 zoom in.
+    pub fn zoom_by(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("zoom by", params, 1);
+        if context.is_empty() { return None; }
+        let factor = params[0].get_value(stack).into_float();
+        if factor <= 0.0 {
+            panic!("error: zoom factor has to be positive, got {factor}"); // TODO: friendlify
+        }
+        let (w, h) = (context.get_width() as f64, context.get_height() as f64);
+        let (hw, hh) = (w / factor / 2.0, h / factor / 2.0);
+        context.push_camera_transform(CameraTransform::Crop(w / 2.0 - hw, h / 2.0 - hh, w / 2.0 + hw, h / 2.0 + hh));
+        None
+    }
+
+    pub fn crop_to(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("crop frame to", params, 1);
+        if context.is_empty() { return None; }
+        let (l, t, r, b) = corners(&params[0].get_value(stack).into_rectangle());
+        if l == r || t == b {
+            panic!("error: cannot crop frame to an empty rectangle"); // TODO: friendlify
+        }
+        context.push_camera_transform(CameraTransform::Crop(l, t, r, b));
+        None
+    }
+
+    pub fn pan_by(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("pan by", params, 1);
+        if context.is_empty() { return None; }
+        let (dx, dy) = params[0].get_value(stack).into_pos().exact();
+        context.push_camera_transform(CameraTransform::Pan(dx, dy));
+        None
+    }
+
+    pub fn rotate_by(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("rotate frame", params, 1);
+        if context.is_empty() { return None; }
+        let degrees = params[0].get_value(stack).into_int();
+        context.push_camera_transform(CameraTransform::Rotate(degrees as f64));
+        None
+    }
+}
+
+pub mod effect {
+    use crate::variable::Effect;
+    use crate::video::Kernel;
+
+    use super::*;
+
+    fn int_param(params: &[Variable], stack: &Stack, i: usize) -> i32 {
+        params[i].get_value(stack).into_int()
+    }
+
+    pub fn brightened(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("brightened by", params, 1);
+        Some(VariableValue::Effect(Effect::Brightness(int_param(params, stack, 0))))
+    }
+
+    pub fn contrasted(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("contrasted by", params, 1);
+        Some(VariableValue::Effect(Effect::Contrast(int_param(params, stack, 0))))
+    }
+
+    pub fn saturated(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("saturated by", params, 1);
+        Some(VariableValue::Effect(Effect::Saturation(int_param(params, stack, 0))))
+    }
+
+    pub fn hue_shifted(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("hue shifted by", params, 1);
+        Some(VariableValue::Effect(Effect::HueShift(int_param(params, stack, 0))))
+    }
+
+    pub fn gamma_corrected(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("gamma corrected by", params, 1);
+        let g = params[0].get_value(stack).into_float();
+        if g <= 0.0 {
+            panic!("error: gamma has to be positive, got {g}"); // TODO: friendlify
+        }
+        Some(VariableValue::Effect(Effect::Gamma(g)))
+    }
+
+    pub fn grayscaled(_context: &mut Context, _stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("grayscaled", params, 0);
+        Some(VariableValue::Effect(Effect::Grayscale))
+    }
+
+    pub fn sepia_toned(_context: &mut Context, _stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("sepia toned", params, 0);
+        Some(VariableValue::Effect(Effect::Sepia))
+    }
+
+    pub fn thresholded(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("thresholded at", params, 1);
+        Some(VariableValue::Effect(Effect::Threshold(int_param(params, stack, 0))))
+    }
+
+    pub fn posterized(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("posterized to", params, 1);
+        let levels = int_param(params, stack, 0);
+        if levels < 2 {
+            panic!("error: posterize needs at least 2 levels, got {levels}"); // TODO: friendlify
+        }
+        Some(VariableValue::Effect(Effect::Posterize(levels)))
+    }
+
+    /// Channel mixing, given by a 3x3 matrix of weights in percent (rows are output channels).
+    pub fn channels_mixed(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("channels mixed by", params, 1);
+        let rows = params[0].get_value(stack).into_vec();
+        let mut m = [[0; 3]; 3];
+        if rows.len() != 3 {
+            panic!("error: channel mixing expects 3 rows, got {}", rows.len()); // TODO: friendlify
+        }
+        for (i, row) in rows.iter().enumerate() {
+            let row = row.get_value(stack).into_vec();
+            if row.len() != 3 {
+                panic!("error: channel mixing expects 3 weights in a row, got {}", row.len()); // TODO: friendlify
+            }
+            for (j, w) in row.iter().enumerate() {
+                m[i][j] = w.get_value(stack).into_int();
+            }
+        }
+        Some(VariableValue::Effect(Effect::ChannelMix(m)))
+    }
+
+    pub fn sharpened(_context: &mut Context, _stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("sharpened", params, 0);
+        Some(VariableValue::Effect(Effect::Sharpen))
+    }
+
+    pub fn embossed(_context: &mut Context, _stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("embossed", params, 0);
+        Some(VariableValue::Effect(Effect::Emboss))
+    }
+
+    pub fn edge_detected(_context: &mut Context, _stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("edge detected", params, 0);
+        Some(VariableValue::Effect(Effect::EdgeDetect))
+    }
+
+    /// Block sizes given by variables below 1 leave pixels as they are (literals are checked when translating).
+    fn block_size(params: &[Variable], stack: &Stack, i: usize) -> i32 {
+        params[i].get_value(stack).into_int().max(1)
+    }
+
+    pub fn pixelated(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("pixelated by", params, 1);
+        Some(VariableValue::Effect(Effect::Pixelate(block_size(params, stack, 0))))
+    }
+
+    pub fn mosaic(_context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("mosaic by", params, 1);
+        Some(VariableValue::Effect(Effect::Mosaic(block_size(params, stack, 0))))
+    }
+
+    pub fn pixelate_frame(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("pixelate frame", params, 1);
+        if context.is_empty() { return None; }
+        let n = block_size(params, stack, 0);
+        context.get_target_mut().draw_effect(Effect::Pixelate(n));
+        None
+    }
+
+    pub fn pixelate_rectangle(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("pixelate rectangle", params, 2);
+        if context.is_empty() { return None; }
+        let r = params[0].get_value(stack).into_rectangle();
+        let n = block_size(params, stack, 1);
+        shape::rect(context, r.top_left, r.bot_right, Fill::Effect(Effect::Pixelate(n)), false);
+        None
+    }
+
+    /// Read a user kernel, given as a square matrix with odd size.
+    fn kernel_of(v: &VariableValue, stack: &Stack) -> Kernel {
+        let rows = v.into_vec().iter()
+            .map(|row| row.get_value(stack).into_vec().iter()
+                .map(|w| w.get_value(stack).into_int() as f64)
+                .collect())
+            .collect();
+        match Kernel::new(rows) {
+            Ok(k) => k,
+            Err(e) => panic!("error: invalid kernel: {e}"), // TODO: friendlify
+        }
+    }
+
+    pub fn apply_kernel_to_frame(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("apply kernel to frame", params, 1);
+        if context.is_empty() { return None; }
+        let k = kernel_of(params[0].get_value(stack), stack);
+        context.get_target_mut().draw_kernel(&k);
+        None
+    }
+
+    pub fn apply_kernel_to_rectangle(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("apply kernel to rectangle", params, 2);
+        if context.is_empty() { return None; }
+        let k = kernel_of(params[0].get_value(stack), stack);
+        let r = params[1].get_value(stack).into_rectangle();
+        let (tl, br) = (r.top_left, r.bot_right);
+        context.get_target_mut().draw_kernel_rect((tl.x as usize, tl.y as usize), (br.x as usize, br.y as usize), &k);
+        None
+    }
+
+    pub fn apply_to_frame(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("apply to frame", params, 1);
+        if context.is_empty() { return None; }
+        let e = params[0].get_value(stack).into_effect();
+        context.get_target_mut().draw_effect(e);
+        None
+    }
+
+    pub fn apply_to_rectangle(context: &mut Context, stack: &mut Stack, params: &mut Vec<Variable>, _action_handles: &mut Vec<ActionHandle>) -> Option<VariableValue> {
+        expect_param_count("apply to rectangle", params, 2);
+        if context.is_empty() { return None; }
+        let e = params[0].get_value(stack).into_effect();
+        let r = params[1].get_value(stack).into_rectangle();
+        shape::rect(context, r.top_left, r.bot_right, Fill::Effect(e), false);
+        None
+    }
+}
@@ -9,6 +9,8 @@ use super::Operation;
 pub enum Event {
     Call(Operation),
     Assignment(String, Operation),
+    /// Events drawing on the layer of the given name
+    Layer(String, Vec<Event>),
 }
 
 impl Event {
@@ -22,6 +24,14 @@ impl Event {
                 stack.update_variable(variable, return_value);
                 None
             }
+            Self::Layer(name, events) => {
+                let previous = context.select_layer(name);
+                for event in events {
+                    event.process(context, stack, action_handles, operations);
+                }
+                context.restore_layer(previous);
+                None
+            }
         }
     }
 }
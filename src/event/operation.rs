@@ -1,6 +1,6 @@
 use std::fmt::{Debug, Display};
 
-use crate::{event::{Event, builtins::Builtin, event::{EventEffect, Operation}}, translator::{Sequence, Signature, parser::OperationMember}, variable::{Scope, Stack, Variable, VariableType}};
+use crate::{event::{Event, builtins::Builtin, event::{EventEffect, Operation}}, translator::{Check, Sequence, Signature, parser::OperationMember}, variable::{Scope, Stack, Variable, VariableType}};
 
 pub type Operations = Vec<OperationTemplateEnum>;
 
@@ -11,12 +11,18 @@ pub struct OperationTemplate {
     effect: EventEffect,
     members: Vec<OperationMember>,
     result: Option<VariableType>,
+    /// Checks of literal arguments, run when translating.
+    checks: &'static [Check],
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum TopLevelOperation {
     LoadFile,
     DoNotSave,
+    DoNotAntialias,
+    KeepHistory,
+    LoadSubtitles,
+    LoadSubtitleFont,
 }
 
 pub enum OperationTemplateEnum {
@@ -35,11 +41,16 @@ impl OperationTemplateEnum {
 
 impl OperationTemplate {
     pub fn new(id: usize, signature: Signature, events: Vec<Event>, members: Vec<OperationMember>, result: Option<VariableType>) -> Self {
-        Self { id, effect: EventEffect::Composed(events), members, signature, result }
+        Self { id, effect: EventEffect::Composed(events), members, signature, result, checks: &[] }
     }
 
-    pub fn from_builtin(id: usize, sequence: Sequence, builtin: Builtin, result: Option<VariableType>) -> Self {
-        Self { id, signature: Signature::from(sequence), effect: EventEffect::Builtin(builtin), members: vec![], result }
+    pub fn from_builtin(id: usize, sequence: Sequence, builtin: Builtin, result: Option<VariableType>, checks: &'static [Check]) -> Self {
+        Self { id, signature: Signature::from(sequence), effect: EventEffect::Builtin(builtin), members: vec![], result, checks }
+    }
+
+    /// Check the literal arguments, the error describes the first invalid one.
+    pub fn check_arguments(&self, params: &[Variable]) -> Result<(), String> {
+        self.checks.iter().try_for_each(|check| check(params))
     }
 
     pub fn get_return_type(&self) -> Option<&VariableType> {
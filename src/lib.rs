@@ -3,7 +3,7 @@ use std::process::exit;
 use context::Context;
 use translator::parser::parse;
 
-use crate::{action::{ActionHandle, process_action_handles}, video::{VideoReader, VideoWriter}};
+use crate::{action::{ActionHandle, process_action_handles}, variable::{Stack, VariableValue}, video::{VideoReader, VideoWriter}};
 
 pub mod action;
 pub mod event;
@@ -13,7 +13,23 @@ pub mod translator;
 pub mod variable;
 
 pub fn run(media_file: String, command_file: String, output_path: String) {
-    let (mut stack, mut actions, operations, options) = match parse(&command_file) {
+    // the program may use properties of the video (e.g. `frame width`) already while it is parsed,
+    // so the video is opened first, failing to open it is reported like a compilation error
+    let mut ffmpeg_input = match video::get_input(&media_file) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("error: could not open video `{media_file}`: {e}");
+            exit(1);
+        }
+    };
+    let mut reader = match VideoReader::new(&mut ffmpeg_input) {
+        Ok(reader) => reader,
+        Err(e) => {
+            eprintln!("error: could not read video `{media_file}`: {e}");
+            exit(1);
+        }
+    };
+    let (mut stack, mut actions, operations, options) = match parse(&command_file, Some(reader.info())) {
         Ok(x) => x,
         Err(e) => {
             e.print();
@@ -21,28 +37,42 @@ pub fn run(media_file: String, command_file: String, output_path: String) {
         },
     };
     let mut action_handles: Vec<ActionHandle> = vec![];
-    let mut ffmpeg_input = video::get_input(&media_file).unwrap();
-    let mut reader = VideoReader::new(&mut ffmpeg_input);
     let mut writer = VideoWriter::from(&reader);
     let mut context = Context::new();
     context.set_reader(&mut reader);
+    context.set_antialias(options.antialias);
+    context.set_history_length(options.history);
+    context.set_subtitles(options.subtitles);
+    if let Some(font) = options.subtitle_font {
+        context.get_subtitle_style_mut().font = font;
+    }
     // let video = Video::from_file(media_file, "ffmpeg").expect("could not read video file");
     // let mut context = Context::from(video);
     // run the main loop
     'main_loop: while context.load_next_frame() {
+        let time = context.get_time_ms();
         for i in 0..actions.len() {
             let a = &mut actions[i];
-            a.step();
+            a.step(time);
             a.trigger(&mut context, &mut stack, &operations, &mut action_handles);
-            let should_stop = process_action_handles(&mut action_handles, &mut actions); // TODO: this has to be
+            let should_stop = process_action_handles(&mut action_handles, &mut actions, &mut context); // TODO: this has to be
                                                                        // changed if action_handle
                                                                        // could reorder actions
             if should_stop {
                 break 'main_loop;
             }
         }
+        update_objects(&mut stack, &mut context);
+        context.finish_frame();
         if options.save_video {
-            writer.append_frame(context.pop_current_frame()).expect("error: failed to append frame to the output");
+            let copies = context.get_frame_copies();
+            let frame = context.pop_current_frame();
+            for _ in 1..copies {
+                writer.append_frame(frame.clone()).expect("error: failed to append frame to the output");
+            }
+            if copies > 0 {
+                writer.append_frame(frame).expect("error: failed to append frame to the output");
+            }
         }
     }
     if options.save_video {
@@ -52,3 +82,28 @@ pub fn run(media_file: String, command_file: String, output_path: String) {
     // let video = context.get_video();
     // video.save(output_path.to_string(), 24, false, "ffmpeg");
 }
+
+/// Draw the sprites and particle emitters stored in global variables on the frame and update them
+/// for the next frame. They are drawn in the alphabetical order of their variables.
+fn update_objects(stack: &mut Stack, context: &mut Context) {
+    let (width, height) = (context.get_width() as u32, context.get_height() as u32);
+    let antialias = context.antialias();
+    let mut objects: Vec<_> = stack.scopes[0].iter_mut()
+        .filter(|(_, v)| matches!(v, VariableValue::Sprite(_) | VariableValue::Emitter(_)))
+        .collect();
+    objects.sort_by(|a, b| a.0.cmp(b.0));
+    for (_, v) in objects {
+        match v {
+            VariableValue::Sprite(s) => {
+                s.draw(&mut context.get_layer_target_mut(s.layer.as_deref()));
+                s.step(width, height);
+            }
+            VariableValue::Emitter(e) => {
+                // new particles are shown in the frame they are emitted in
+                e.step();
+                e.draw(&mut context.get_layer_target_mut(e.layer.as_deref()), antialias);
+            }
+            _ => unreachable!(),
+        }
+    }
+}
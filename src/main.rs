@@ -37,7 +37,179 @@ fn main() {
   top [Any(1)] into Any(1)                      - put the top-most element of a vector into a variable
   add Int to Int                                - add some number to a variable
   toggle Str                                    - toggle an action with that name
-  sub Int from Int                              - subtract some number from a variable");
+  sub Int from Int                              - subtract some number from a variable
+  move Pos Dir by Float                         - move the position by a fraction of a pixel and wrap around
+  move Pos by Float and Float                   - move the position by a fractional offset
+  move Pos by Pos clamping to edges             - move by a velocity, stop at the edges of the frame
+  move Pos by Pos wrapping around edges         - move by a velocity, come back from the opposite edge
+  move Pos by Pos bouncing off edges            - move by a velocity, flip the velocity at the edges
+  move Rectangle by Pos clamping to edges / wrapping around edges / bouncing off edges
+  round Float / floor Float                     - convert a float to an integer
+  Float as Int / Int as Float                   - convert between floats and integers
+  add Float to Float / sub Float from Float     - float arithmetic on a variable
+  multiply Float by Float / divide Float by Float - float arithmetic on a variable
+  true / false                                  - boolean values
+  not Bool / Bool and Bool / Bool or Bool       - boolean logic
+  Int is greater than Int / Int is less than Int - compare two numbers
+  Any(1) equals Any(1)                          - check whether two values are equal
+  Pos is inside Rectangle                       - check whether a position lies in a rectangle
+  Rectangle overlaps Rectangle                  - check whether two rectangles overlap
+  activate Str if Bool / deactivate Str if Bool - (de)activate action with that name if the condition holds
+  activate Str when Rectangle hits Rectangle    - activate action while the rectangles overlap
+  activate Str when Pos hits Rectangle          - activate action while the position is inside
+  stop if Bool                                  - stop the program if the condition holds
+  skip this frame                               - do not write the current frame to the output
+  repeat this frame Int times                   - write the current frame again Int more times
+  mix Color with Color by Int                   - mix in a percentage of the second color
+  lighten Color by Int / darken Color by Int    - mix in a percentage of white/black
+  color with hue Int saturation Int value Int   - color from hue (degrees), saturation and value (percent)
+  color name = Color;                           - (top-level) name a color, the name can be used as a color afterwards
+  load Str                                      - (top-level) load a program, or the named colors of a GIMP palette (.gpl)
+  frame width / frame height                    - dimensions of the video
+  frame center / frame rectangle                - center and the whole area of the frame
+  current frame / current time                  - index of the current frame (from 1) and its time in ms
+  video fps / total frames                      - frame rate and the number of frames of the video
+  color at Pos / color at Pos of Image          - color of a pixel in the frame or in an image
+  average color of Rectangle                    - average color of a region of the frame
+  average color of Image                        - average color of an image
+  brightness of Rectangle                       - average brightness (0-255) of a region of the frame
+  histogram of Rectangle                        - brightness histogram (256 bins) of a region of the frame
+  contrasting color for Color                   - black or white, whichever is more readable on the color
+
+Shapes (drawn with a Color, a Gradient or an Effect, positions are pixels the shape goes through;
+edges are smooth unless the program says `do not antialias`):
+  draw Color line from Pos to Pos width Int
+  draw Color circle at Pos radius Int
+  draw Color circle outline at Pos radius Int width Int
+  draw Color ellipse at Pos radius Int and Int
+  draw Color ellipse outline at Pos radius Int and Int width Int
+  draw Color polygon [Pos]
+  draw Color polygon outline [Pos] width Int
+  draw Color polyline [Pos] width Int
+  draw Color rounded rectangle from Pos to Pos radius Int
+  draw Color rounded rectangle outline from Pos to Pos radius Int width Int
+
+Gradients (can be drawn wherever a Color rectangle can):
+  gradient from Color at Pos to Color at Pos             - linear gradient between two points
+  radial gradient from Color at Pos to Color at radius Int - gradient by the distance from a point
+  fill frame with Color / fill frame with Gradient        - paint the whole frame
+
+Image transforms (change the image in place):
+  scale Image to Int x Int / scale Image by Float - resize the image
+  rotate Image by Int degrees                     - rotate clockwise, the image grows to fit
+  flip Image horizontally / flip Image vertically - mirror the image
+  crop Image to Rectangle                         - keep only a part of the image
+
+Green screen (images have transparency, draw Image at Pos blends them by it):
+  key out Color in Image with tolerance Int       - make colors close to Color transparent
+  suppress Color spill in Image                   - remove the tint of the screen from the rest
+  feather edges of Image by Int                   - soften the edges of the opaque parts
+
+Masks (limit drawing to a part of the frame, partially opaque parts blend):
+  circle mask at Pos radius Int / ellipse mask at Pos radius Int and Int
+  polygon mask [Pos] / mask of Rectangle
+  rounded rectangle mask from Pos to Pos radius Int
+  luminance mask of Image at Pos                  - bright parts of the image are opaque
+  alpha mask of Image at Pos                      - opaque parts of the image are opaque
+  invert Mask                                     - e.g. for vignettes
+  feather Mask by Int                             - soften the edges of the mask
+  draw Color through Mask / draw Effect through Mask / draw Gradient through Mask
+  draw Image at Pos through Mask
+
+History (the program has to say `keep Int frames of history`, at most that many frames back;
+finished frames are counted as written to the output, a repeated frame once per copy, a skipped one not at all):
+  frame Int ago                                   - image of a finished frame (0 is the current frame)
+  original frame Int ago                          - image of a frame as it was in the input
+  draw Image at Pos with opacity Int              - draw an image partially transparent (percent)
+  difference between Image and Image              - absolute difference of the colors
+
+Timing (actions and triggers in frames count output frames, `current frame` is the frame of the input):
+  set speed to Float                              - 2.0 is twice as fast, frames are dropped or repeated
+  set speed to Float with blending                - the same, frames are blended instead
+  freeze for Int frames                           - repeat the current input frame
+  reverse from Int to Int                         - play these input frames backwards once they are reached
+
+Timestamps (actions in seconds or milliseconds follow the timestamps of the input frames, so
+`at 90 seconds` is at 1:30 of the input and `every 2 seconds` is at 2s, 4s, ... even with a variable
+frame rate, reversed or slowed down input; timestamps are written as 00:01:30.500, 1:30, 90.5s or 500ms):
+  at 00:01:30.500 {{ ... }}                        - once the input reaches the time
+  from 10s to 20s {{ ... }}                        - every frame in the window, the end is exclusive
+  every 5 frames from 10s to 20s {{ ... }}         - any trigger can be limited to a window
+
+Sprites (kept in global variables, drawn and moved by their velocity every frame):
+  sprite of Image at Pos                          - Pos is the center of the sprite
+  sprite of Color rectangle Int by Int at Pos / sprite of Color circle radius Int at Pos
+  move Sprite by Pos / move Sprite to Pos
+  set velocity of Sprite to Pos                   - movement per frame
+  bounce Sprite off edges                         - the sprite stays within the frame from now on
+  clamp Sprite to edges / wrap Sprite around edges
+  Sprite hits Sprite                              - check whether two visible sprites overlap
+  activate Str when Sprite hits Sprite            - activate action while the sprites overlap
+  hide Sprite / show Sprite
+  rotate Sprite by Int / scale Sprite by Float
+  set opacity of Sprite to Int                    - percent
+  put Sprite on layer String                      - draw the sprite on the layer instead of the frame
+  position of Sprite / rectangle of Sprite
+
+Subtitles (the program says `load subtitles from String` with an .srt or .vtt file next to it, the cue of the
+current input time is drawn over the output; the built-in font only has ASCII characters, other ones are drawn as `?`):
+  load subtitle font from String                  - (top-level) use a BDF bitmap font, e.g. for accented letters
+  set subtitle position to Pos                    - bottom center of the text
+  set subtitle size to Int                        - height of a line in pixels
+  set subtitle color to Color                     - text color, the outline is black
+
+Particles (emitters kept in global variables emit, move and draw particles every frame):
+  emitter at Pos rate Int per frame               - number of new particles per frame
+  move Emitter to Pos / set area of Emitter to Rectangle - particles appear anywhere in the area
+  set rate of Emitter to Int / set lifetime of Emitter to Int frames
+  set velocity of Emitter to Pos                  - initial velocity of particles
+  set spread of Emitter to Float                  - random change of the initial velocity
+  set gravity of Emitter to Float                 - added to the vertical velocity every frame
+  set color of Emitter to Color / set color of Emitter from Color to Color
+  set size of Emitter to Int / set size of Emitter from Int to Int - radius over the life of particles
+  seed Emitter with Int                           - the same seed gives the same particles
+  pause Emitter / resume Emitter                  - stop and start emitting new particles
+  put Emitter on layer String                     - draw the particles on the layer instead of the frame
+
+Layers (drawn over the frame once it is done, cleared for each frame):
+  on layer \"name\" {{ ... }}                        - the events in the block draw on the layer
+  set z index of layer String to Int              - higher layers are drawn over lower ones
+  set opacity of layer String to Int              - opacity of the whole layer in percent
+  set blend mode of layer String to String        - normal, multiply, screen, overlay, add or difference
+
+Transitions (between the output and a still image, e.g. `frame 1 ago` frozen before jumping in the footage):
+  crossfade / dip to Color / wipe Direction / slide Direction / zoom in
+  transition to Image with Transition for Int frames   - the output turns into the still image
+  transition from Image with Transition for Int frames - the still image turns into the output
+
+Camera (applied to the output once the frame is done, the output keeps its size):
+  zoom to Rectangle                               - show only the area, keeping the aspect ratio
+  zoom by Float                                   - zoom around the center of the frame
+  crop frame to Rectangle                         - stretch the area over the whole frame
+  pan by Pos                                      - move the picture by an offset
+  rotate frame by Int degrees                     - rotate the picture around the center
+
+Color grading (Effects, usable wherever an Effect is):
+  brightened by Int                               - add to all channels
+  contrasted by Int percent                       - 100 keeps the contrast
+  saturated by Int percent                        - 100 keeps the saturation, 0 is gray
+  hue shifted by Int                              - rotate hue by degrees
+  gamma corrected by Float                        - above 1 brightens the shadows
+  grayscaled / sepia toned
+  thresholded at Int                              - black and white by brightness
+  posterized to Int levels                        - reduce the number of shades
+  channels mixed by [[Int]]                       - 3x3 channel weights in percent
+  apply Effect to frame / apply Effect to Rectangle
+
+Spatial filters:
+  blurred / sharpened / embossed / edge detected  - Effects done by convolution
+  apply kernel [[Int]] to Rectangle               - convolve with an odd-sized square kernel,
+  apply kernel [[Int]] to frame                     normalized by the sum of its weights
+
+Censoring:
+  pixelated by Int / mosaic by Int                - Effects replacing blocks of the size by their average
+  pixelate Rectangle by Int                       - pixelate a region of the frame
+  pixelate frame by Int                           - pixelate the whole frame");
         return;
     }
     let Some(video_path) = args.video_path else {
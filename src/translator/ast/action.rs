@@ -10,6 +10,8 @@ use super::Sequence;
 pub enum Time {
     Variable(String, Range),
     Number(i64, Range),
+    /// `00:01:30.500`, `90.5s` or `500ms`, it is checked by the parser
+    Timestamp(String, Range),
 }
 
 #[derive(Debug, Clone)]
@@ -24,7 +26,10 @@ pub struct Trigger {
     pub onetime: bool,
     pub active: bool,
     pub time: Time,
-    pub unit: Unit,
+    /// Missing for timestamps and for triggers with only a window (`from 10s to 20s`)
+    pub unit: Option<Unit>,
+    /// `from 10s to 20s`, the trigger only runs while the input is in the window
+    pub window: Option<(Time, Time)>,
     pub range: Range,
 }
 
@@ -41,18 +46,30 @@ pub enum Event {
     Operation(Sequence, Range),
     Assignment(Assignment, Range),
     VarDefinition(VarDefinition, Range),
+    /// `on layer "overlay" { ... }`, the events draw on the layer
+    Layer(String, Vec<Event>, Range),
 }
 
 impl Action {
     pub fn find_variable_definition(&self, name: &str) -> Range {
-        for e in &self.events {
-            let Event::VarDefinition(d, r) = e else { continue; };
-            if &d.name.0 == name {
-                return r.clone();
+        find_variable_definition(&self.events, name)
+            .unwrap_or_else(|| panic!("could not find variable definition for `{name}`"))
+    }
+}
+
+fn find_variable_definition(events: &[Event], name: &str) -> Option<Range> {
+    for e in events {
+        match e {
+            Event::VarDefinition(d, r) if d.name.0 == name => return Some(r.clone()),
+            Event::Layer(_, events, _) => {
+                if let Some(r) = find_variable_definition(events, name) {
+                    return Some(r);
+                }
             }
+            _ => {}
         }
-        panic!("could not find variable definition for `{name}`");
     }
+    None
 }
 
 impl AstBuilder {
@@ -67,10 +84,12 @@ impl AstBuilder {
     pub fn get_trigger(&self, node: &Node) -> Trigger {
         self.expect_node_kind(node, "trigger");
         let active = node.child_by_field_name("deactivated").is_none();
-        let onetime = self.get_repeat_quantifier(&node.child_by_field_name("onetime").unwrap());
+        let onetime = node.child_by_field_name("onetime").is_some_and(|n| self.get_repeat_quantifier(&n));
         let time = node.child_by_field_name("step").map_or(Time::Number(1, Range::from(node)), |n| self.get_time(&n));
-        let unit = self.get_unit(&node.child_by_field_name("unit").unwrap());
-        Trigger { onetime, active, time, unit, range: Range::from(node) }
+        let unit = node.child_by_field_name("unit").map(|n| self.get_unit(&n));
+        let window = node.child_by_field_name("from").zip(node.child_by_field_name("to"))
+            .map(|(from, to)| (self.get_time(&from), self.get_time(&to)));
+        Trigger { onetime, active, time, unit, window, range: Range::from(node) }
     }
 
     pub fn get_repeat_quantifier(&self, node: &Node) -> bool {
@@ -96,6 +115,7 @@ impl AstBuilder {
         match node.kind() {
             "number" => Time::Number(self.get_number(node), Range::from(node)),
             "variable" => Time::Variable(self.get_variable(node), Range::from(node)),
+            "timestamp" => Time::Timestamp(self.text(node).to_string(), Range::from(node)),
             x => panic!("error: unexpected node kind for time {node:?}: {x}"),
         }
     }
@@ -119,6 +139,11 @@ impl AstBuilder {
             "sequence" => Event::Operation(self.get_sequence(&child), Range::from(node)),
             "assignment" => Event::Assignment(self.get_var_assignment(&child), Range::from(node)),
             "var_definition" => Event::VarDefinition(self.get_var_definition(&child), Range::from(node)),
+            "layer_block" => {
+                let layer = self.get_string(&child.child_by_field_name("layer").unwrap());
+                let events = self.get_events(&child.child_by_field_name("events").unwrap());
+                Event::Layer(layer, events, Range::from(node))
+            }
             x => panic!("error: unexpected node kind for event: `{x}")
         }
     }
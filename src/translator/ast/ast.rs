@@ -1,10 +1,11 @@
 use crate::translator::ast::{Range, Sequence};
 
-use super::{Action, VarDefinition, Definition, AstBuilder};
+use super::{Action, VarDefinition, ColorDefinition, Definition, AstBuilder, grammar};
 
 pub enum AstNode {
     Action(Action),
     VarDefinition(VarDefinition),
+    ColorDefinition(ColorDefinition),
     Definition(Definition),
     Sequence(Sequence),
     // FileLoad(String),
@@ -18,14 +19,14 @@ pub struct Ast {
 impl Ast {
     pub fn parse(filepath: &str) -> Self {
         let mut parser = tree_sitter::Parser::new();
-        parser.set_language(&tree_sitter_vinx::LANGUAGE.into()).expect("error: could not load vinx grammar");
+        grammar::load(&mut parser);
         let contents = std::fs::read_to_string(filepath).expect("error reading input file");
         Self::parse_from_contents(filepath, contents)
     }
 
     pub fn parse_from_contents(filepath: &str, contents: String) -> Self {
         let mut parser = tree_sitter::Parser::new();
-        parser.set_language(&tree_sitter_vinx::LANGUAGE.into()).expect("error: could not load vinx grammar");
+        grammar::load(&mut parser);
         let tree = parser.parse(&contents, None).unwrap();
         let root_node = tree.root_node();
         let builder = AstBuilder::new(filepath, contents.to_string());
@@ -35,6 +36,7 @@ impl Ast {
                 "comment" | ";" => { continue; }
                 "action" => AstNode::Action(builder.get_action(&node)),
                 "var_definition" => AstNode::VarDefinition(builder.get_var_definition(&node)),
+                "color_definition" => AstNode::ColorDefinition(builder.get_color_definition(&node)),
                 "definition" => AstNode::Definition(builder.get_definition(&node)),
                 "sequence" => AstNode::Sequence(builder.get_sequence(&node)),
                 x => panic!("error: unexpected top-level node: `{x}")
@@ -51,6 +53,7 @@ impl Ast {
                 "comment" | ";" => { continue; }
                 "action" => AstNode::Action(builder.get_action(&node)),
                 "var_definition" => AstNode::VarDefinition(builder.get_var_definition(&node)),
+                "color_definition" => AstNode::ColorDefinition(builder.get_color_definition(&node)),
                 "definition" => AstNode::Definition(builder.get_definition(&node)),
                 "sequence" => AstNode::Sequence(builder.get_sequence(&node)),
                 x => panic!("error: unexpected top-level node: `{x}")
@@ -99,4 +102,26 @@ mod tests {
             ");
         assert_eq!(ast.nodes.len(), 4);
     }
+
+    #[test]
+    fn test_color_definition() {
+        let ast = ast!("color brand = #1a73e8;\ncolor accent = lighten brand by 20;");
+        assert_eq!(ast.nodes.len(), 2);
+        let AstNode::ColorDefinition(d) = &ast.nodes[1].0 else {
+            panic!("expected a color definition");
+        };
+        assert_eq!(d.name.0, "accent");
+        assert_eq!(d.value.0.len(), 4);
+    }
+
+    #[test]
+    fn test_timestamp_triggers() {
+        let ast = ast!("at 00:01:30.500 { skip this frame; }\nfrom 10s to 20s { on layer \"top\" { skip this frame; } }");
+        let AstNode::Action(a) = &ast.nodes[0].0 else { panic!("expected an action") };
+        assert!(matches!(&a.trigger.time, super::super::Time::Timestamp(t, _) if t == "00:01:30.500"));
+        assert!(a.trigger.onetime && a.trigger.unit.is_none() && a.trigger.window.is_none());
+        let AstNode::Action(a) = &ast.nodes[1].0 else { panic!("expected an action") };
+        assert!(!a.trigger.onetime && a.trigger.window.is_some());
+        assert!(matches!(&a.events[0], super::super::Event::Layer(name, events, _) if name == "top" && events.len() == 1));
+    }
 }
@@ -0,0 +1,52 @@
+//! The tree-sitter-vinx grammar lives in its own repository, so a checkout of it may be older
+//! than the AST builder. Parts added to the grammar later are listed here, an old grammar is then
+//! reported with what is missing instead of turning new syntax into error nodes.
+
+use tree_sitter::Language;
+
+/// Named node kinds the grammar did not have at first, with the syntax they are used for.
+const NODE_KINDS: &[(&str, &str)] = &[
+    ("float", "Float values, e.g. `0.5`"),
+    ("color_definition", "color definitions, e.g. `color brand = #1a73e8;`"),
+    ("layer_block", "layer blocks, e.g. `on layer \"text\" { ... }`"),
+    ("timestamp", "timestamps of the input, e.g. `at 00:01:30.500`"),
+];
+
+/// Field names the grammar did not have at first, with the syntax they are used for.
+const FIELDS: &[(&str, &str)] = &[
+    ("value", "the color of a color definition"),
+    ("layer", "the layer name of a layer block"),
+    ("from", "the start of a trigger window, e.g. `every 5 frames from 10s to 20s`"),
+    ("to", "the end of a trigger window"),
+];
+
+/// Parts of the grammar (node kinds and fields) missing in `language`.
+pub fn missing_parts(language: &Language) -> Vec<String> {
+    let kinds = NODE_KINDS.iter()
+        .filter(|(kind, _)| language.id_for_node_kind(kind, true) == 0)
+        .map(|(kind, syntax)| format!("node `{kind}` ({syntax})"));
+    let fields = FIELDS.iter()
+        .filter(|(field, _)| language.field_id_for_name(field).is_none())
+        .map(|(field, syntax)| format!("field `{field}` ({syntax})"));
+    kinds.chain(fields).collect()
+}
+
+/// Set the vinx grammar to `parser`, it has to provide every part the AST builder uses.
+pub fn load(parser: &mut tree_sitter::Parser) {
+    let language: Language = tree_sitter_vinx::LANGUAGE.into();
+    let missing = missing_parts(&language);
+    if !missing.is_empty() {
+        panic!("error: the vinx grammar (tree-sitter-vinx) is too old, it is missing: {}", missing.join(", "));
+    }
+    parser.set_language(&language).expect("error: could not load vinx grammar");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grammar_is_up_to_date() {
+        assert_eq!(missing_parts(&tree_sitter_vinx::LANGUAGE.into()), Vec::<String>::new());
+    }
+}
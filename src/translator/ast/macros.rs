@@ -12,9 +12,11 @@ macro_rules! ast {
     ( $x:expr ) => { Ast::parse_from_contents("tmp.vinx", $x.to_string()) };
 }
 
+use super::grammar;
+
 pub fn parse(contents: &str) -> tree_sitter::Tree {
     let mut parser = tree_sitter::Parser::new();
-    parser.set_language(&tree_sitter_vinx::LANGUAGE.into()).expect("error: could not load vinx grammar");
+    grammar::load(&mut parser);
     parser.parse(&contents, None).unwrap()
 }
 
@@ -10,12 +10,13 @@ mod var_definition;
 mod builder;
 mod range;
 mod macros;
+mod grammar;
 
 pub use ast::{Ast, AstNode};
 pub use signature::{Signature, Iterator};
 pub use action::{Action, Trigger, Time, Unit, Event};
 pub use definition::Definition;
-pub use var_definition::{VarDefinition,Assignment};
+pub use var_definition::{VarDefinition,Assignment,ColorDefinition};
 pub use sequence::Sequence;
 pub use value::Value;
 pub use range::Range;
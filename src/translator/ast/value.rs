@@ -1,7 +1,7 @@
 use tree_sitter::Node;
 
 use super::AstBuilder;
-use crate::variable::{Direction, Effect};
+use crate::variable::{Direction, Effect, color};
 
 type Color = (u8, u8, u8); // TODO: add alpha
 type Position = (i64, i64);
@@ -10,6 +10,7 @@ type Position = (i64, i64);
 pub enum Value {
     Variable(String),
     Number(i64),
+    Float(f64),
     Position(Position),
     Color(Color),
     Effect(Effect),
@@ -29,6 +30,7 @@ impl AstBuilder {
             "effect" => Value::Effect(self.get_effect(&val)),
             "direction" => Value::Direction(self.get_direction(&val)),
             "number" => Value::Number(self.get_number(&val)),
+            "float" => Value::Float(self.get_float(&val)),
             "string" => Value::String(self.get_string(&val)),
             "vector" => Value::Vector(self.get_vector(&val)),
             x => {
@@ -54,6 +56,11 @@ impl AstBuilder {
         self.text(&node).parse().expect(&format!("error reading number value of node {}",node.to_string()))
     }
 
+    pub fn get_float(&self, node: &Node) -> f64 {
+        self.expect_node_kind(node, "float");
+        self.text(node).parse().unwrap_or_else(|_| panic!("error reading float value of node {node}"))
+    }
+
     pub fn get_string(&self, node: &Node) -> String {
         self.expect_node_kind(node, "string");
         let str = self.text(node);
@@ -94,22 +101,11 @@ impl AstBuilder {
     }
 
     fn get_color_by_name(&self, node: &Node) -> Color {
-        match self.text(node) {
-            "red" => (255,0,0),
-            "green" => (0,255,0),
-            "blue" => (0,0,255),
-            "yellow" => (255,225,53),
-            "black" => (0,0,0),
-            "white" => (255,255,255),
-            "orange" => (255,165,0),
-            "pink" => (255,192,203),
-            "purple" => (128,0,128),
-            "brown" => (165,42,42),
-            "cyan" => (0,255,255),
-            x => {
-                panic!("error: unknown color name {}",x);
-            }
-        }
+        let name = self.text(node);
+        let Some(c) = color::color_by_name(name) else {
+            panic!("error: unknown color name {}",name);
+        };
+        (c[0],c[1],c[2])
     }
 
     fn get_color_by_val(&self, node: &Node) -> Color {
@@ -11,6 +11,13 @@ pub struct VarDefinition {
     pub value: Option<(Sequence, Range)>,
 }
 
+/// `color brand = #1a73e8;`
+#[derive(Debug, Clone)]
+pub struct ColorDefinition {
+    pub name: (String, Range),
+    pub value: (Sequence, Range),
+}
+
 #[derive(Debug)]
 pub struct Assignment {
     pub name: (String, Range),
@@ -37,4 +44,13 @@ impl AstBuilder {
         let value = (self.get_sequence(&value_node), Range::from(&value_node));
         Assignment { name, value }
     }
+
+    pub fn get_color_definition(&self, node: &Node) -> ColorDefinition {
+        self.expect_node_kind(node, "color_definition");
+        let name_node = node.child_by_field_name("name").unwrap();
+        let name = (self.get_keyword(&name_node), Range::from(&name_node));
+        let value_node = node.child_by_field_name("value").unwrap();
+        let value = (self.get_sequence(&value_node), Range::from(&value_node));
+        ColorDefinition { name, value }
+    }
 }
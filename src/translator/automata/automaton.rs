@@ -52,6 +52,12 @@ impl Automaton {
         true
     }
 
+    /// Whether `word` is a keyword of any registered sequence.
+    pub fn is_keyword(&self, word: &str) -> bool {
+        let word = Word::Keyword(word.to_string());
+        self.states.iter().any(|s| s.has_transition(&word))
+    }
+
     /// Performs a run of automaton over sequence `seq` and returns its value (if it exists).
     pub fn run(&self, seq: &Vec<Word>) -> Option<SequenceValue> {
         // if seq.len() == 1 && let Some(t) = seq[0].get_type() {
@@ -219,7 +225,7 @@ mod tests {
             if !aut.register(seq.clone(), SequenceType::Operation) {
                 panic!("error: union did not create any new states");
             }
-            ops.push(OperationTemplateEnum::Standard(OperationTemplate::from_builtin(i, seq.clone(), *op, ret.clone())));
+            ops.push(OperationTemplateEnum::Standard(OperationTemplate::from_builtin(i, seq.clone(), *op, ret.clone(), &[])));
         }
         (aut,ops)
     }
@@ -258,6 +264,14 @@ mod tests {
         assert_eq!(a.run(seq!("a" Pos "b").get()), None);
     }
 
+    #[test]
+    fn test_is_keyword() {
+        let a = Automaton::from((seq!("draw" Color "to" Pos), SequenceType::Operation));
+        assert!(a.is_keyword("draw"));
+        assert!(a.is_keyword("to"));
+        assert!(!a.is_keyword("red"));
+    }
+
     #[test]
     fn test_automaton_priority_choice() {
         let la1 = (seq!(a Int b), SequenceType::Operation);
@@ -1,29 +1,32 @@
 use crate::event::{OperationTemplate, OperationTemplateEnum, TopLevelOperation, builtins::*};
 use crate::translator::sequence::SequenceType;
+use crate::translator::checks::*;
 use crate::{seq, word, vtype};
 use crate::variable::VariableType;
 
 use super::{automata::Automaton, SequenceValue, Word, StructureTemplate, Sequence};
 
 macro_rules! builtin {
-    (($($w:tt)+), $f:expr, $r:expr) => {
-        ((seq!($($w)+)), Some($r), $f)
+    (($($w:tt)+), $f:expr, [$($c:expr),*], $r:expr) => {
+        ((seq!($($w)+)), Some($r), $f, &[$($c),*])
     };
 
-    (($($w:tt)+), $f:expr) => {
-        ((seq!($($w)+)), None, $f)
+    (($($w:tt)+), $f:expr, [$($c:expr),*]) => {
+        ((seq!($($w)+)), None, $f, &[$($c),*])
     };
 }
 
+/// Builtins are written as `(sequence) => return type, function, checks of literal arguments`,
+/// the return type and the checks are optional.
 macro_rules! builtins {
     (
         $(
-            ($($w:tt)+) $(=> $r:expr)?, $f:expr
+            ($($w:tt)+) $(=> $r:expr)?, $f:expr $(, $c:expr)*
         );* $(;)?
     ) => {
         [
             $(
-                builtin!(($($w)+), $f $(, $r)?)
+                builtin!(($($w)+), $f, [$($c),*] $(, $r)?)
             ),*
         ]
     };
@@ -34,6 +37,10 @@ pub fn load_top_level_operations(aut: &mut Automaton) -> Vec<OperationTemplateEn
     let builtins: &[(Sequence, TopLevelOperation)] = &[
         (seq!("load" String), TopLevelOperation::LoadFile),
         (seq!("do" "not" "save"), TopLevelOperation::DoNotSave),
+        (seq!("do" "not" "antialias"), TopLevelOperation::DoNotAntialias),
+        (seq!("keep" Int "frames" "of" "history"), TopLevelOperation::KeepHistory),
+        (seq!("load" "subtitles" "from" String), TopLevelOperation::LoadSubtitles),
+        (seq!("load" "subtitle" "font" "from" String), TopLevelOperation::LoadSubtitleFont),
     ];
     let mut ops = vec![];
     for (seq,f) in builtins {
@@ -47,24 +54,35 @@ pub fn load_top_level_operations(aut: &mut Automaton) -> Vec<OperationTemplateEn
 
 /// Generate all builtin operations, note them in the automaton `aut` and return them.
 pub fn load_builtin_operations(aut: &mut Automaton) -> Vec<OperationTemplateEnum> {
-    let builtins: &[(Sequence, Option<VariableType>, Builtin)] = &builtins!(
+    let builtins: &[(Sequence, Option<VariableType>, Builtin, &[Check])] = &builtins!(
         ((Any(0))) => VariableType::Any(0), get_value;
         ("restricted" "move" Pos Direction "by" Int), move_pos;
         ("move" Pos Direction "by" Int), move_pos_phase;
+        ("move" Pos Direction "by" Float), move_pos_phase;
         ("draw" Color "rectangle" "outline" "from" Pos "to" Pos), draw_rect_outline;
+        ("draw" Gradient "rectangle" "outline" "from" Pos "to" Pos), draw_rect_outline;
         ("activate" String), activate;
         ("deactivate" String), deactivate;
         ("stop"), stop;
+        ("skip" "this" "frame"), skip;
+        ("repeat" "this" "frame" Int "times"), repeat, non_negative::<0>;
         ("set" (Any(0)) "to" (Any(0))), set;
         ("rotate" [(Any(0))] Direction "by" Int), rotate_vec;
         ("top" [(Any(0))] "into" (Any(0))), top_into;
         ("top" [Any(0)]) => VariableType::Any(0), top;
         ("add" Int "to" Int), add_to;
         ("draw" Color "rectangle" "from" Pos "to" Pos), draw_rect;
+        ("draw" Gradient "rectangle" "from" Pos "to" Pos), draw_rect;
         ("draw" Effect "rectangle" "from" Pos "to" Pos), draw_effect_rect;
         ("toggle" String), toggle_activeness;
         ("sub" Int "from" Int), sub;
         ("move" Pos "by" Pos), move_by;
+        ("move" Pos "by" Pos "clamping" "to" "edges"), move_clamped;
+        ("move" Pos "by" Pos "wrapping" "around" "edges"), move_wrapped;
+        ("move" Pos "by" Pos "bouncing" "off" "edges"), move_bouncing;
+        ("move" Rectangle "by" Pos "clamping" "to" "edges"), move_clamped;
+        ("move" Rectangle "by" Pos "wrapping" "around" "edges"), move_wrapped;
+        ("move" Rectangle "by" Pos "bouncing" "off" "edges"), move_bouncing;
         ("print" String), print;
         ("get" "frame") => VariableType::Image, get_frame;
         ("move" Rectangle "by" Pos), rectangle::move_by;
@@ -72,9 +90,102 @@ pub fn load_builtin_operations(aut: &mut Automaton) -> Vec<OperationTemplateEnum
         ("get" "corner" "of" Rectangle) => VariableType::Pos, rectangle::get_corner;
         ("draw" Color Rectangle), rectangle::draw;
         ("draw" Color "outline" "of" Rectangle), rectangle::draw_outline;
+        ("draw" Gradient Rectangle), rectangle::draw;
+        ("draw" Gradient "outline" "of" Rectangle), rectangle::draw_outline;
         ("draw" Image "at" Pos), image::draw_at;
+        ("draw" Image "at" Pos "with" "opacity" Int), image::draw_at_with_opacity;
+        ("difference" "between" Image "and" Image) => VariableType::Image, image::difference;
+        ("frame" Int "ago") => VariableType::Image, history::frame_ago, non_negative::<0>;
+        ("original" "frame" Int "ago") => VariableType::Image, history::original_frame_ago, non_negative::<0>;
+        ("set" "speed" "to" Float), timing::speed, positive::<0>;
+        ("set" "speed" "to" Float "with" "blending"), timing::speed_blended, positive::<0>;
+        ("freeze" "for" Int "frames"), timing::freeze, non_negative::<0>;
+        ("reverse" "from" Int "to" Int), timing::reverse, ordered::<0, 1>;
+        ("sprite" "of" Image "at" Pos) => VariableType::Sprite, sprite::of_image;
+        ("sprite" "of" Color "rectangle" Int "by" Int "at" Pos) => VariableType::Sprite, sprite::of_rectangle, non_negative::<1>, non_negative::<2>;
+        ("sprite" "of" Color "circle" "radius" Int "at" Pos) => VariableType::Sprite, sprite::of_circle, non_negative::<1>;
+        ("move" Sprite "by" Pos), sprite::move_by;
+        ("move" Sprite "to" Pos), sprite::move_to;
+        ("set" "velocity" "of" Sprite "to" Pos), sprite::set_velocity;
+        ("bounce" Sprite "off" "edges"), sprite::bounce;
+        ("clamp" Sprite "to" "edges"), sprite::clamp;
+        ("wrap" Sprite "around" "edges"), sprite::wrap;
+        (Sprite "hits" Sprite) => VariableType::Bool, sprite::hits;
+        ("activate" String "when" Sprite "hits" Sprite), sprite::activate_on_hit;
+        ("hide" Sprite), sprite::hide;
+        ("show" Sprite), sprite::show;
+        ("rotate" Sprite "by" Int), sprite::rotate;
+        ("scale" Sprite "by" Float), sprite::scale, non_negative::<1>;
+        ("set" "opacity" "of" Sprite "to" Int), sprite::set_opacity;
+        ("put" Sprite "on" "layer" String), sprite::put_on_layer;
+        ("position" "of" Sprite) => VariableType::Pos, sprite::position;
+        ("rectangle" "of" Sprite) => VariableType::Rectangle, sprite::rectangle;
+        ("emitter" "at" Pos "rate" Int "per" "frame") => VariableType::Emitter, emitter::new, non_negative::<1>;
+        ("move" Emitter "to" Pos), emitter::move_to;
+        ("set" "area" "of" Emitter "to" Rectangle), emitter::set_area;
+        ("set" "rate" "of" Emitter "to" Int), emitter::set_rate, non_negative::<1>;
+        ("set" "lifetime" "of" Emitter "to" Int "frames"), emitter::set_lifetime, non_negative::<1>;
+        ("set" "velocity" "of" Emitter "to" Pos), emitter::set_velocity;
+        ("set" "spread" "of" Emitter "to" Float), emitter::set_spread;
+        ("set" "gravity" "of" Emitter "to" Float), emitter::set_gravity;
+        ("set" "color" "of" Emitter "to" Color), emitter::set_color;
+        ("set" "color" "of" Emitter "from" Color "to" Color), emitter::set_colors;
+        ("set" "size" "of" Emitter "to" Int), emitter::set_size, non_negative::<1>;
+        ("set" "size" "of" Emitter "from" Int "to" Int), emitter::set_sizes, non_negative::<1>, non_negative::<2>;
+        ("seed" Emitter "with" Int), emitter::seed;
+        ("pause" Emitter), emitter::pause;
+        ("resume" Emitter), emitter::resume;
+        ("put" Emitter "on" "layer" String), emitter::put_on_layer;
+        ("set" "subtitle" "position" "to" Pos), subtitles::set_position;
+        ("set" "subtitle" "size" "to" Int), subtitles::set_size, positive::<0>;
+        ("set" "subtitle" "color" "to" Color), subtitles::set_color;
+        ("set" "z" "index" "of" "layer" String "to" Int), layer::set_z_index;
+        ("set" "opacity" "of" "layer" String "to" Int), layer::set_opacity, percent::<1>;
+        ("set" "blend" "mode" "of" "layer" String "to" String), layer::set_blend_mode, blend_mode::<1>;
+        ("crossfade") => VariableType::Transition, transition::crossfade;
+        ("dip" "to" Color) => VariableType::Transition, transition::dip;
+        ("wipe" Direction) => VariableType::Transition, transition::wipe;
+        ("slide" Direction) => VariableType::Transition, transition::slide;
+        ("zoom" "in") => VariableType::Transition, transition::zoom;
+        ("transition" "to" Image "with" Transition "for" Int "frames"), transition::to, positive::<2>;
+        ("transition" "from" Image "with" Transition "for" Int "frames"), transition::from, positive::<2>;
         ("save" Image "as" String), image::save_as;
         ("draw" Color Rectangle "into" Image), image::draw_into;
+        ("scale" Image "to" Int "x" Int), image::scale_to, non_negative::<1>, non_negative::<2>;
+        ("scale" Image "by" Float), image::scale_by, non_negative::<1>;
+        ("rotate" Image "by" Int "degrees"), image::rotate;
+        ("flip" Image "horizontally"), image::flip_horizontally;
+        ("flip" Image "vertically"), image::flip_vertically;
+        ("crop" Image "to" Rectangle), image::crop;
+        ("key" "out" Color "in" Image "with" "tolerance" Int), image::key_out;
+        ("suppress" Color "spill" "in" Image), image::suppress_spill;
+        ("feather" "edges" "of" Image "by" Int), image::feather, non_negative::<1>;
+        ("zoom" "to" Rectangle), camera::zoom_to;
+        ("zoom" "by" Float), camera::zoom_by, positive::<0>;
+        ("crop" "frame" "to" Rectangle), camera::crop_to;
+        ("pan" "by" Pos), camera::pan_by;
+        ("rotate" "frame" "by" Int "degrees"), camera::rotate_by;
+        ("brightened" "by" Int) => VariableType::Effect, effect::brightened;
+        ("contrasted" "by" Int "percent") => VariableType::Effect, effect::contrasted;
+        ("saturated" "by" Int "percent") => VariableType::Effect, effect::saturated;
+        ("hue" "shifted" "by" Int) => VariableType::Effect, effect::hue_shifted;
+        ("gamma" "corrected" "by" Float) => VariableType::Effect, effect::gamma_corrected, positive::<0>;
+        ("grayscaled") => VariableType::Effect, effect::grayscaled;
+        ("sepia" "toned") => VariableType::Effect, effect::sepia_toned;
+        ("thresholded" "at" Int) => VariableType::Effect, effect::thresholded;
+        ("posterized" "to" Int "levels") => VariableType::Effect, effect::posterized, at_least::<0, 2>;
+        ("channels" "mixed" "by" [[Int]]) => VariableType::Effect, effect::channels_mixed, channel_matrix::<0>;
+        ("sharpened") => VariableType::Effect, effect::sharpened;
+        ("embossed") => VariableType::Effect, effect::embossed;
+        ("edge" "detected") => VariableType::Effect, effect::edge_detected;
+        ("pixelated" "by" Int) => VariableType::Effect, effect::pixelated, at_least::<0, 1>;
+        ("mosaic" "by" Int) => VariableType::Effect, effect::mosaic, at_least::<0, 1>;
+        ("pixelate" "frame" "by" Int), effect::pixelate_frame, at_least::<0, 1>;
+        ("pixelate" Rectangle "by" Int), effect::pixelate_rectangle, at_least::<1, 1>;
+        ("apply" "kernel" [[Int]] "to" "frame"), effect::apply_kernel_to_frame, kernel::<0>;
+        ("apply" "kernel" [[Int]] "to" Rectangle), effect::apply_kernel_to_rectangle, kernel::<0>;
+        ("apply" Effect "to" "frame"), effect::apply_to_frame;
+        ("apply" Effect "to" Rectangle), effect::apply_to_rectangle;
         ("rectangle" "from" Pos "to" Pos) => VariableType::Rectangle, rectangle::new;
         (Color "image" Int "x" Int) => VariableType::Image, image::colored;
         ("load" "image" "from" String) => VariableType::Image, image::load_from;
@@ -85,13 +196,103 @@ pub fn load_builtin_operations(aut: &mut Automaton) -> Vec<OperationTemplateEnum
         ("take" "row" "at" Int) => VariableType::Row, row::take;
         ("append" Row "to" Image), row::append;
         ("prepend" Row "to" Image), row::prepend;
+        ("round" Float) => VariableType::Int, float::round;
+        ("floor" Float) => VariableType::Int, float::floor;
+        (Float "as" "Int") => VariableType::Int, float::as_int;
+        (Int "as" "Float") => VariableType::Float, float::from_int;
+        ("add" Float "to" Float), float::add_to;
+        ("sub" Float "from" Float), float::sub;
+        ("multiply" Float "by" Float), float::multiply;
+        ("divide" Float "by" Float), float::divide, nonzero::<1>;
+        ("move" Pos "by" Float "and" Float), float::move_by;
+        ("true") => VariableType::Bool, logic::truth;
+        ("false") => VariableType::Bool, logic::falsity;
+        ("not" Bool) => VariableType::Bool, logic::not;
+        (Bool "and" Bool) => VariableType::Bool, logic::and;
+        (Bool "or" Bool) => VariableType::Bool, logic::or;
+        (Int "is" "greater" "than" Int) => VariableType::Bool, logic::is_greater;
+        (Int "is" "less" "than" Int) => VariableType::Bool, logic::is_less;
+        ((Any(0)) "equals" (Any(0))) => VariableType::Bool, logic::equals;
+        (Pos "is" "inside" Rectangle) => VariableType::Bool, logic::is_inside;
+        (Rectangle "overlaps" Rectangle) => VariableType::Bool, logic::overlaps;
+        ("activate" String "when" Rectangle "hits" Rectangle), logic::activate_on_overlap;
+        ("activate" String "when" Pos "hits" Rectangle), logic::activate_on_inside;
+        ("activate" String "if" Bool), logic::activate_if;
+        ("deactivate" String "if" Bool), logic::deactivate_if;
+        ("stop" "if" Bool), logic::stop_if;
+        ("mix" Color "with" Color "by" Int) => VariableType::Color, color::mix;
+        ("lighten" Color "by" Int) => VariableType::Color, color::lighten;
+        ("darken" Color "by" Int) => VariableType::Color, color::darken;
+        ("color" "with" "hue" Int "saturation" Int "value" Int) => VariableType::Color, color::from_hsv;
+        ("frame" "width") => VariableType::Int, frame::width;
+        ("frame" "height") => VariableType::Int, frame::height;
+        ("frame" "center") => VariableType::Pos, frame::center;
+        ("frame" "rectangle") => VariableType::Rectangle, frame::rectangle;
+        ("current" "frame") => VariableType::Int, frame::current;
+        ("current" "time") => VariableType::Int, frame::time;
+        ("video" "fps") => VariableType::Float, frame::fps;
+        ("total" "frames") => VariableType::Int, frame::count;
+        ("color" "at" Pos) => VariableType::Color, sample::color_at;
+        ("color" "at" Pos "of" Image) => VariableType::Color, sample::color_at_image;
+        ("average" "color" "of" Rectangle) => VariableType::Color, sample::average_color;
+        ("average" "color" "of" Image) => VariableType::Color, sample::average_color_image;
+        ("brightness" "of" Rectangle) => VariableType::Int, sample::brightness;
+        ("histogram" "of" Rectangle) => vtype!([Int]), sample::histogram;
+        ("contrasting" "color" "for" Color) => VariableType::Color, sample::contrasting;
+        ("draw" Color "line" "from" Pos "to" Pos "width" Int), shape::line, non_negative::<3>;
+        ("draw" Effect "line" "from" Pos "to" Pos "width" Int), shape::line, non_negative::<3>;
+        ("draw" Gradient "line" "from" Pos "to" Pos "width" Int), shape::line, non_negative::<3>;
+        ("draw" Color "circle" "at" Pos "radius" Int), shape::circle, non_negative::<2>;
+        ("draw" Effect "circle" "at" Pos "radius" Int), shape::circle, non_negative::<2>;
+        ("draw" Gradient "circle" "at" Pos "radius" Int), shape::circle, non_negative::<2>;
+        ("draw" Color "circle" "outline" "at" Pos "radius" Int "width" Int), shape::circle_outline, non_negative::<2>, non_negative::<3>;
+        ("draw" Effect "circle" "outline" "at" Pos "radius" Int "width" Int), shape::circle_outline, non_negative::<2>, non_negative::<3>;
+        ("draw" Gradient "circle" "outline" "at" Pos "radius" Int "width" Int), shape::circle_outline, non_negative::<2>, non_negative::<3>;
+        ("draw" Color "ellipse" "at" Pos "radius" Int "and" Int), shape::ellipse, non_negative::<2>, non_negative::<3>;
+        ("draw" Effect "ellipse" "at" Pos "radius" Int "and" Int), shape::ellipse, non_negative::<2>, non_negative::<3>;
+        ("draw" Gradient "ellipse" "at" Pos "radius" Int "and" Int), shape::ellipse, non_negative::<2>, non_negative::<3>;
+        ("draw" Color "ellipse" "outline" "at" Pos "radius" Int "and" Int "width" Int), shape::ellipse_outline, non_negative::<2>, non_negative::<3>, non_negative::<4>;
+        ("draw" Effect "ellipse" "outline" "at" Pos "radius" Int "and" Int "width" Int), shape::ellipse_outline, non_negative::<2>, non_negative::<3>, non_negative::<4>;
+        ("draw" Gradient "ellipse" "outline" "at" Pos "radius" Int "and" Int "width" Int), shape::ellipse_outline, non_negative::<2>, non_negative::<3>, non_negative::<4>;
+        ("draw" Color "polygon" [Pos]), shape::polygon;
+        ("draw" Effect "polygon" [Pos]), shape::polygon;
+        ("draw" Gradient "polygon" [Pos]), shape::polygon;
+        ("draw" Color "polygon" "outline" [Pos] "width" Int), shape::polygon_outline, non_negative::<2>;
+        ("draw" Effect "polygon" "outline" [Pos] "width" Int), shape::polygon_outline, non_negative::<2>;
+        ("draw" Gradient "polygon" "outline" [Pos] "width" Int), shape::polygon_outline, non_negative::<2>;
+        ("draw" Color "polyline" [Pos] "width" Int), shape::polyline, non_negative::<2>;
+        ("draw" Effect "polyline" [Pos] "width" Int), shape::polyline, non_negative::<2>;
+        ("draw" Gradient "polyline" [Pos] "width" Int), shape::polyline, non_negative::<2>;
+        ("draw" Color "rounded" "rectangle" "from" Pos "to" Pos "radius" Int), shape::rounded_rect, non_negative::<3>;
+        ("draw" Effect "rounded" "rectangle" "from" Pos "to" Pos "radius" Int), shape::rounded_rect, non_negative::<3>;
+        ("draw" Gradient "rounded" "rectangle" "from" Pos "to" Pos "radius" Int), shape::rounded_rect, non_negative::<3>;
+        ("draw" Color "rounded" "rectangle" "outline" "from" Pos "to" Pos "radius" Int "width" Int), shape::rounded_rect_outline, non_negative::<3>, non_negative::<4>;
+        ("draw" Effect "rounded" "rectangle" "outline" "from" Pos "to" Pos "radius" Int "width" Int), shape::rounded_rect_outline, non_negative::<3>, non_negative::<4>;
+        ("draw" Gradient "rounded" "rectangle" "outline" "from" Pos "to" Pos "radius" Int "width" Int), shape::rounded_rect_outline, non_negative::<3>, non_negative::<4>;
+        ("gradient" "from" Color "at" Pos "to" Color "at" Pos) => VariableType::Gradient, gradient::linear;
+        ("radial" "gradient" "from" Color "at" Pos "to" Color "at" "radius" Int) => VariableType::Gradient, gradient::radial, non_negative::<3>;
+        ("fill" "frame" "with" Color), shape::fill_frame;
+        ("fill" "frame" "with" Gradient), shape::fill_frame;
+        ("circle" "mask" "at" Pos "radius" Int) => VariableType::Mask, mask::circle, non_negative::<1>;
+        ("ellipse" "mask" "at" Pos "radius" Int "and" Int) => VariableType::Mask, mask::ellipse, non_negative::<1>, non_negative::<2>;
+        ("polygon" "mask" [Pos]) => VariableType::Mask, mask::polygon;
+        ("mask" "of" Rectangle) => VariableType::Mask, mask::rectangle;
+        ("rounded" "rectangle" "mask" "from" Pos "to" Pos "radius" Int) => VariableType::Mask, mask::rounded_rect, non_negative::<2>;
+        ("luminance" "mask" "of" Image "at" Pos) => VariableType::Mask, mask::luminance;
+        ("alpha" "mask" "of" Image "at" Pos) => VariableType::Mask, mask::alpha;
+        ("invert" Mask), mask::invert;
+        ("feather" Mask "by" Int), mask::feather, non_negative::<1>;
+        ("draw" Color "through" Mask), mask::draw_through;
+        ("draw" Effect "through" Mask), mask::draw_through;
+        ("draw" Gradient "through" Mask), mask::draw_through;
+        ("draw" Image "at" Pos "through" Mask), mask::draw_image_through;
     );
     let mut ops = vec![];
-    for (i,(seq,ret,op)) in builtins.into_iter().enumerate() {
+    for (i,(seq,ret,op,checks)) in builtins.iter().enumerate() {
         if !aut.register(seq.clone(), SequenceType::Operation) {
             panic!("error: union did not create any new states");
         }
-        ops.push(OperationTemplateEnum::Standard(OperationTemplate::from_builtin(i, seq.clone(), *op, ret.clone())));
+        ops.push(OperationTemplateEnum::Standard(OperationTemplate::from_builtin(i, seq.clone(), *op, ret.clone(), checks)));
     }
     ops
 }
@@ -0,0 +1,161 @@
+//! Checks of literal arguments of builtin operations, done when translating.
+//!
+//! Only static parameters (e.g. `-5` or `[[1,2],[3,4]]`) are checked, the builtins still
+//! check values of variables when running.
+
+use crate::{variable::{Variable, VariableValue}, video::{BlendMode, Kernel}};
+
+/// Check of the parameters of an operation, the error describes the invalid argument.
+pub type Check = fn(&[Variable]) -> Result<(), String>;
+
+fn literal(params: &[Variable], i: usize) -> Option<&VariableValue> {
+    match params.get(i)? {
+        Variable::Static(v) => Some(v),
+        Variable::Named(..) => None,
+    }
+}
+
+fn number(params: &[Variable], i: usize) -> Option<f64> {
+    match literal(params, i)? {
+        VariableValue::Int(n) => Some(*n as f64),
+        VariableValue::Float(x) => Some(*x),
+        _ => None,
+    }
+}
+
+pub fn nonzero<const I: usize>(params: &[Variable]) -> Result<(), String> {
+    if number(params, I) == Some(0.0) {
+        return Err("expected a nonzero number, got 0".to_string());
+    }
+    Ok(())
+}
+
+pub fn non_negative<const I: usize>(params: &[Variable]) -> Result<(), String> {
+    match number(params, I) {
+        Some(n) if n < 0.0 => Err(format!("expected a non-negative number, got {n}")),
+        _ => Ok(()),
+    }
+}
+
+pub fn positive<const I: usize>(params: &[Variable]) -> Result<(), String> {
+    match number(params, I) {
+        Some(n) if n <= 0.0 => Err(format!("expected a positive number, got {n}")),
+        _ => Ok(()),
+    }
+}
+
+pub fn at_least<const I: usize, const MIN: i32>(params: &[Variable]) -> Result<(), String> {
+    match number(params, I) {
+        Some(n) if n < MIN as f64 => Err(format!("expected at least {MIN}, got {n}")),
+        _ => Ok(()),
+    }
+}
+
+/// Rows of a literal matrix, `None` if any of the values is not known yet.
+fn matrix(params: &[Variable], i: usize) -> Option<Vec<Vec<f64>>> {
+    let VariableValue::Vec(rows) = literal(params, i)? else {
+        return None;
+    };
+    rows.iter().map(|row| {
+        let Variable::Static(VariableValue::Vec(row)) = row else {
+            return None;
+        };
+        row.iter().map(|w| match w {
+            Variable::Static(VariableValue::Int(n)) => Some(*n as f64),
+            _ => None,
+        }).collect()
+    }).collect()
+}
+
+/// A 3x3 matrix of channel weights.
+pub fn channel_matrix<const I: usize>(params: &[Variable]) -> Result<(), String> {
+    let Some(rows) = matrix(params, I) else {
+        return Ok(());
+    };
+    if rows.len() != 3 || rows.iter().any(|r| r.len() != 3) {
+        return Err("expected a 3x3 matrix of channel weights".to_string());
+    }
+    Ok(())
+}
+
+pub fn kernel<const I: usize>(params: &[Variable]) -> Result<(), String> {
+    match matrix(params, I) {
+        Some(rows) => Kernel::new(rows).map(|_| ()),
+        None => Ok(()),
+    }
+}
+
+/// The `B`-th parameter must not be less than the `A`-th one (e.g. `reverse from 10 to 5`).
+pub fn ordered<const A: usize, const B: usize>(params: &[Variable]) -> Result<(), String> {
+    match (number(params, A), number(params, B)) {
+        (Some(a), Some(b)) if b < a => Err(format!("{b} is less than {a}")),
+        _ => Ok(()),
+    }
+}
+
+pub fn percent<const I: usize>(params: &[Variable]) -> Result<(), String> {
+    match number(params, I) {
+        Some(n) if !(0.0..=100.0).contains(&n) => Err(format!("expected a percentage between 0 and 100, got {n}")),
+        _ => Ok(()),
+    }
+}
+
+pub fn blend_mode<const I: usize>(params: &[Variable]) -> Result<(), String> {
+    match literal(params, I) {
+        Some(VariableValue::String(mode)) if BlendMode::from_name(mode).is_none()
+            => Err(format!("unknown blend mode `{mode}`, expected one of normal, multiply, screen, overlay, add, difference")),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::variable::{Variable, VariableValue};
+    use super::*;
+
+    fn ints(ns: &[i32]) -> Vec<Variable> {
+        ns.iter().map(|n| Variable::Static(VariableValue::Int(*n))).collect()
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert!(non_negative::<1>(&ints(&[-1, 0])).is_ok());
+        assert!(non_negative::<1>(&ints(&[0, -1])).is_err());
+        assert!(positive::<0>(&ints(&[0])).is_err());
+        assert!(positive::<0>(&[Variable::Static(VariableValue::Float(0.5))]).is_ok());
+        assert!(at_least::<0, 2>(&ints(&[1])).is_err());
+        assert!(ordered::<0, 1>(&ints(&[10, 5])).is_err());
+        assert!(ordered::<0, 1>(&ints(&[5, 5])).is_ok());
+        assert!(percent::<0>(&ints(&[101])).is_err());
+        assert!(percent::<0>(&ints(&[100])).is_ok());
+        assert!(nonzero::<1>(&[Variable::Static(VariableValue::Int(0)), Variable::Static(VariableValue::Float(0.5))]).is_ok());
+        assert!(nonzero::<0>(&[Variable::Static(VariableValue::Float(0.0))]).is_err());
+    }
+
+    fn rows(rows: &[&[i32]]) -> Vec<Variable> {
+        let rows = rows.iter().map(|r| Variable::Static(VariableValue::Vec(ints(r)))).collect();
+        vec![Variable::Static(VariableValue::Vec(rows))]
+    }
+
+    #[test]
+    fn test_matrices() {
+        assert!(kernel::<0>(&rows(&[&[1, 1, 1], &[1, 1, 1], &[1, 1, 1]])).is_ok());
+        assert!(kernel::<0>(&rows(&[&[1, 1], &[1, 1]])).is_err());
+        assert!(channel_matrix::<0>(&rows(&[&[100, 0, 0], &[0, 100, 0], &[0, 0, 100]])).is_ok());
+        assert!(channel_matrix::<0>(&rows(&[&[100, 0, 0], &[0, 100, 0]])).is_err());
+    }
+
+    #[test]
+    fn test_blend_mode() {
+        assert!(blend_mode::<0>(&[Variable::Static(VariableValue::String("screen".to_string()))]).is_ok());
+        assert!(blend_mode::<0>(&[Variable::Static(VariableValue::String("lighter".to_string()))]).is_err());
+    }
+
+    #[test]
+    fn test_variables_are_not_checked() {
+        let params = vec![Variable::Named("x".to_string(), crate::variable::VariableType::Float)];
+        assert!(nonzero::<0>(&params).is_ok());
+        assert!(non_negative::<0>(&params).is_ok());
+        assert!(channel_matrix::<0>(&params).is_ok());
+    }
+}
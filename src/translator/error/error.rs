@@ -1,4 +1,4 @@
-use crate::translator::Sequence;
+use crate::{translator::Sequence, variable::VariableType};
 use super::Location;
 
 pub enum CompilationError {
@@ -8,10 +8,15 @@ pub enum CompilationError {
     ForbiddenVariableName(String, Location),
     UnknownVariableName(String, Location),
     FileNotFound(String, Option<Location>),
+    InvalidFile(String, String, Location),
+    MismatchedType(VariableType, VariableType, Location), // expected and found type
+    InvalidArgument(String, Location),
     MemberUsedBeforeDefinition(String, Location),
     DuplicateMemberName(String, Location, Location),
     RecursiveFileDependency(String, String, Location),
     MultipleMainIterators(Location),
+    InvalidColorName(String, Location),
+    ReservedColorName(String, Location),
     VagueDefinition(Location, Location, Location), // the definition is neither structure nor operation
                                                    // the params are: 1) signature, 2) first sequence, 3) first method
 }
@@ -56,6 +61,18 @@ impl CompilationError {
                     eprintln!("{}", loc.get_source());
                 }
             }
+            Self::InvalidFile(fp, reason, loc) => {
+                print_err!("could not read file `{fp}`: {reason}");
+                eprintln!("{}", loc.get_source());
+            }
+            Self::MismatchedType(expected, found, loc) => {
+                print_err!("expected a value of type {expected}, found {found}");
+                eprintln!("{}", loc.get_source());
+            }
+            Self::InvalidArgument(msg, loc) => {
+                print_err!("{msg}");
+                eprintln!("{}", loc.get_source());
+            }
             Self::RecursiveFileDependency(fp1, fp2, loc) => {
                 print_err!("files `{fp1}` and `{fp2}` are recursively dependent on each other");
                 eprintln!("{}", loc.get_source());
@@ -64,6 +81,16 @@ impl CompilationError {
                 print_err!("multiple iterators set as main");
                 eprintln!("{}", loc.get_source());
             }
+            Self::InvalidColorName(name, loc) => {
+                print_err!("invalid color name `{name}`");
+                eprint!("{}", loc.get_source());
+                print_note!("color names have to be single lowercase words, such as `brand_blue`");
+            }
+            Self::ReservedColorName(name, loc) => {
+                print_err!("color name `{name}` is a keyword of a sequence");
+                eprint!("{}", loc.get_source());
+                print_note!("the color could not be told apart from the keyword, choose another name");
+            }
             Self::DuplicateMemberName(name, loc1, first_loc) => {
                 print_err!("duplicate definition of local variable `{name}`");
                 eprint!("{}", loc1.get_source());
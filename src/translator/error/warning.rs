@@ -5,6 +5,8 @@ use super::Location;
 pub enum Warning {
     RedundantFileLoad(String, Location),
     OperationWithoutInterpretation(Signature, Location),
+    RedefinedColor(String, Location),
+    SkippedPaletteColor(String, Location),
 }
 
 impl Warning {
@@ -19,6 +21,14 @@ impl Warning {
                 eprintln!("operation `{sig}` does not have any interpretation");
                 eprintln!("{}", loc.get_source());
             }
+            Self::RedefinedColor(name, loc) => {
+                eprintln!("color `{name}` is already defined: overriding");
+                eprintln!("{}", loc.get_source());
+            }
+            Self::SkippedPaletteColor(name, loc) => {
+                eprintln!("palette color `{name}` cannot be used as a color name: skipping");
+                eprintln!("{}", loc.get_source());
+            }
         }
     }
 }
@@ -75,6 +75,13 @@ impl FileManager {
         filepath.is_file()
     }
 
+    /// Path of a file given relative to the currently processed file.
+    pub fn resolve(&self, filename: &str) -> PathBuf {
+        let mut path = self.get_current_directory();
+        path.push(filename);
+        path
+    }
+
     /// Returns the directory of currently processed file
     fn get_current_directory(&self) -> PathBuf {
         let mut path = self.get_current_pathbuf().clone();
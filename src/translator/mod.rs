@@ -10,6 +10,7 @@ mod builtins;
 mod sequence;
 mod structure_template;
 mod file_manager;
+mod checks;
 pub mod ast;
 
 pub mod parser;
@@ -17,5 +18,6 @@ pub mod parser;
 pub use signature::Signature;
 pub use sequence::{SequenceValue, Sequence};
 pub use structure_template::StructureTemplate;
+pub use checks::Check;
 
 use word::Word;
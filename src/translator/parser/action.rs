@@ -1,15 +1,83 @@
-use crate::{action::{Action, Trigger}, event::{Event, Operation}, translator::{SequenceValue, ast::{self, Range}, error::CompilationError, parser::parser::Parser}};
+use crate::{action::{Action, TimeUnit, Trigger}, event::{Event, Operation}, translator::{SequenceValue, ast::{self, Range}, error::CompilationError, parser::{OperationMember, parser::Parser}}, variable::{Variable, VariableType, VariableValue}, video};
 
 impl Parser {
     pub fn parse_action(&mut self, action: &ast::Action) -> Result<(), CompilationError> {
         if !action.trigger.active && action.label.is_none() {
             return Ok(());
         }
-        let trigger = Trigger::from(action.trigger.clone(), &self.globals);
-        let mut events = vec![];
+        let trigger = self.parse_trigger(&action.trigger)?;
         let mut locals = vec![];
         self.globals.push();
-        for event in &action.events {
+        let events = self.parse_events(action, &action.events, &mut locals)?;
+        self.globals.pop();
+        let a = Action::new(action.label.clone().unwrap_or("".to_string()), events, trigger, locals);
+        self.actions.push(a);
+        Ok(())
+    }
+
+    fn parse_trigger(&self, trigger: &ast::Trigger) -> Result<Trigger, CompilationError> {
+        let unit = match (&trigger.unit, &trigger.time) {
+            (_, ast::Time::Timestamp(..)) => TimeUnit::Millisecond,
+            (None | Some(ast::Unit::Frame(_)), _) => TimeUnit::Frame,
+            (Some(ast::Unit::Second(_)), _) => TimeUnit::Second,
+            (Some(ast::Unit::Millisecond(_)), _) => TimeUnit::Millisecond,
+        };
+        let time = match &trigger.time {
+            ast::Time::Variable(name, range) => {
+                let Some(var) = self.globals.get_variable(name) else {
+                    return Err(CompilationError::UnknownVariableName(name.clone(), self.get_location(range)));
+                };
+                if var.get_type() != VariableType::Int {
+                    return Err(CompilationError::MismatchedType(VariableType::Int, var.get_type(), self.get_location(range)));
+                }
+                Variable::Named(name.clone(), VariableType::Int)
+            }
+            ast::Time::Number(n, range) => Variable::Static(VariableValue::Int(self.check_trigger_time(*n, range)?)),
+            ast::Time::Timestamp(text, range) => {
+                let ms = self.get_timestamp(text, range)?;
+                Variable::Static(VariableValue::Int(self.check_trigger_time(ms as i64, range)?))
+            }
+        };
+        let mut t = Trigger::new(time, unit, trigger.onetime);
+        if let Some((from, to)) = &trigger.window {
+            let (from, to) = (self.get_window_time(from)?, self.get_window_time(to)?);
+            if to <= from {
+                let msg = "the window of the trigger ends before it starts".to_string();
+                return Err(CompilationError::InvalidArgument(msg, self.get_location(&trigger.range)));
+            }
+            t.set_window(from, to);
+        }
+        if !trigger.active {
+            t.disable();
+        }
+        Ok(t)
+    }
+
+    fn check_trigger_time(&self, time: i64, range: &Range) -> Result<i32, CompilationError> {
+        i32::try_from(time).ok().filter(|t| *t >= 0).ok_or_else(|| {
+            let msg = format!("trigger time has to be between 0 and {}, got {time}", i32::MAX);
+            CompilationError::InvalidArgument(msg, self.get_location(range))
+        })
+    }
+
+    fn get_window_time(&self, time: &ast::Time) -> Result<usize, CompilationError> {
+        match time {
+            ast::Time::Timestamp(text, range) => self.get_timestamp(text, range),
+            ast::Time::Variable(_, range) | ast::Time::Number(_, range) => {
+                let msg = "the window of a trigger is given by timestamps, e.g. `from 10s to 1:30`".to_string();
+                Err(CompilationError::InvalidArgument(msg, self.get_location(range)))
+            }
+        }
+    }
+
+    fn get_timestamp(&self, text: &str, range: &Range) -> Result<usize, CompilationError> {
+        video::parse_timestamp(text).map_err(|e| CompilationError::InvalidArgument(e, self.get_location(range)))
+    }
+
+    /// Translate the events of an action, local variables defined by them are added to `locals`.
+    fn parse_events(&mut self, action: &ast::Action, ast_events: &[ast::Event], locals: &mut Vec<OperationMember>) -> Result<Vec<Event>, CompilationError> {
+        let mut events = vec![];
+        for event in ast_events {
             match event {
                 ast::Event::Operation(op, _) => events.push(Event::Call(self.get_operation(op)?)),
                 ast::Event::Assignment(assignment, _) => {
@@ -23,6 +91,7 @@ impl Parser {
                     let Some(sv) = self.automaton.run(seq.get()) else {
                         panic!() // TODO: friendlify
                     };
+                    self.check_arguments(&sv, params, definition.get_value_range())?;
                     let SequenceValue::Operation(op_id) = sv else {
                         panic!() // TODO: FIXME
                     };
@@ -40,12 +109,13 @@ impl Parser {
                     locals.push((definition.get_name().clone(), return_type.clone()));
                     events.push(Event::Assignment(definition.get_name().clone(), op));
                 }
+                ast::Event::Layer(layer, layer_events, _) => {
+                    let layer_events = self.parse_events(action, layer_events, locals)?;
+                    events.push(Event::Layer(layer.clone(), layer_events));
+                }
             }
         }
-        self.globals.pop();
-        let a = Action::new(action.label.clone().unwrap_or("".to_string()), events, trigger, locals);
-        self.actions.push(a);
-        Ok(())
+        Ok(events)
     }
 
     pub fn get_operation(&mut self, event: &ast::Sequence) -> Result<Operation, CompilationError> {
@@ -57,6 +127,7 @@ impl Parser {
             // TODO: handle returning
             panic!("error: unexpected seq value {:?}", sv);
         };
+        self.check_arguments(&sv, &params, &Range::from(event))?;
         let event;
         event = self.operations[x].get().instantiate(params);
         Ok(event)
@@ -84,12 +84,11 @@ impl Parser {
     pub fn define_variable(&mut self, var_definition: &ast::VarDefinition) -> Result<(), CompilationError> {
         let var_definition = self.get_var_definition(var_definition, None)?;
         let (seq, params) = var_definition.get_value();
-        let value = match self.automaton.run(seq.get()) {
-            Some(sv) => sv.into_value(params.clone(), &self.operations, &self.structures, &mut self.globals), // FIXME so that we dont clone params
-            None => {
-                return Err(CompilationError::UnknownSequence(seq.clone(), self.get_location(var_definition.get_value_range())))
-            }
+        let Some(sv) = self.automaton.run(seq.get()) else {
+            return Err(CompilationError::UnknownSequence(seq.clone(), self.get_location(var_definition.get_value_range())))
         };
+        self.check_arguments(&sv, params, var_definition.get_value_range())?;
+        let value = sv.into_value(params.clone(), &mut self.get_context(), &self.operations, &self.structures, &mut self.globals); // FIXME so that we dont clone params
         let name = var_definition.get_name();
         if self.is_forbidden_variable_name(name) {
             return Err(CompilationError::ForbiddenVariableName(name.clone(), self.get_location(var_definition.get_value_range())));
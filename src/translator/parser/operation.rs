@@ -220,9 +220,10 @@ impl Parser {
                 ast::definition::Statement::VarDefinition(d) => {
                     let definition = self.get_var_definition(d, None)?;
                     let (seq,params) = definition.get_value();
-                    let Some(SequenceValue::Operation(op_id)) = self.automaton.run(seq.get()) else {
+                    let Some(sv @ SequenceValue::Operation(op_id)) = self.automaton.run(seq.get()) else {
                         panic!() // TODO: friendlify
                     };
+                    self.check_arguments(&sv, params, definition.get_value_range())?;
                     let mut event = self.operations[op_id].get().instantiate(params.clone());
                     self.deactivate_struct_for_event(&mut event, structure);
                     events.push(Event::Assignment(d.name.0.clone(), event));
@@ -1,11 +1,20 @@
+use crate::video::{Font, Subtitles};
+
 /// Options are set from within the vinx program.
 pub struct Options {
     pub save_video: bool,
+    pub antialias: bool,
+    /// Number of past frames kept by the runtime.
+    pub history: usize,
+    /// Subtitles drawn over the output.
+    pub subtitles: Option<Subtitles>,
+    /// Font of the subtitles, the built-in font is used if it is not set.
+    pub subtitle_font: Option<Font>,
 }
 
 impl Options {
     /// Create Options with default values.
     pub fn default() -> Self {
-        Self { save_video: true }
+        Self { save_video: true, antialias: true, history: 0, subtitles: None, subtitle_font: None }
     }
 }
@@ -1,4 +1,6 @@
-use crate::{action::Action, event::{Operations, TopLevelOperation}, translator::{StructureTemplate, ast::{self, Ast, AstNode, Range}, automata::Automaton, builtins::{load_builtin_operations, load_builtin_structures, load_top_level_operations}, error::{CompilationError, Location, Warning}, file_manager::FileManager, parser::options::Options}, variable::Stack};
+use std::collections::HashMap;
+
+use crate::{action::Action, context::Context, event::{OperationTemplateEnum, Operations, TopLevelOperation}, translator::{SequenceValue, StructureTemplate, ast::{self, Ast, AstNode, Range}, automata::Automaton, builtins::{load_builtin_operations, load_builtin_structures, load_top_level_operations}, error::{CompilationError, Location, Warning}, file_manager::FileManager, parser::options::Options}, variable::{Color, Stack, Variable, VariableType, color}, video::{Font, Subtitles, VideoInfo}};
 
 pub struct Parser {
     pub globals: Stack,
@@ -11,12 +13,16 @@ pub struct Parser {
     pub _unresolved_parameter_types: usize,
     pub self_reference_name: &'static str,
     pub warnings: Vec<Warning>,
+    /// Colors named by the program (`color brand = #1a73e8;`) or loaded from palettes
+    pub colors: HashMap<String, Color>,
+    /// Properties of the processed video, if known
+    video_info: Option<VideoInfo>,
     options: Options,
 }
 
 impl Parser {
     // Creates a new parser with loaded builtins.
-    pub fn new(filepath: &str) -> Result<Self, CompilationError> {
+    pub fn new(filepath: &str, video_info: Option<VideoInfo>) -> Result<Self, CompilationError> {
         let mut aut = Automaton::new();
         let mut operations = load_builtin_operations(&mut aut);
         operations.append(&mut load_top_level_operations(&mut aut));
@@ -36,6 +42,8 @@ impl Parser {
             _unresolved_parameter_types: 0,
             self_reference_name: "$self",
             warnings: vec![],
+            colors: HashMap::new(),
+            video_info,
             options: Options::default(),
         })
     }
@@ -47,6 +55,7 @@ impl Parser {
                 AstNode::Action(a) => self.parse_action(a)?,
                 AstNode::Definition(d) => self.parse_definition(d)?,
                 AstNode::VarDefinition(d) => self.define_variable(d)?,
+                AstNode::ColorDefinition(d) => self.parse_color_definition(d)?,
                 AstNode::Sequence(s) => {
                     let (seq, params) = self.parse_sequence(s)?;
                     let Some(sv) = self.automaton.run(seq.get()) else {
@@ -56,14 +65,48 @@ impl Parser {
                         match top_level_op {
                             TopLevelOperation::LoadFile => {
                                 let filepath = params[0].get_value(&self.globals).into_string().to_string();
-                                self.parse_file_load(&filepath, &Range::from(s))?;
+                                if filepath.ends_with(".gpl") {
+                                    self.load_palette(&filepath, &Range::from(s))?;
+                                } else {
+                                    self.parse_file_load(&filepath, &Range::from(s))?;
+                                }
                             }
                             TopLevelOperation::DoNotSave => {
                                 self.options.save_video = false;
                             }
+                            TopLevelOperation::DoNotAntialias => {
+                                self.options.antialias = false;
+                            }
+                            TopLevelOperation::KeepHistory => {
+                                let length = params[0].get_value(&self.globals).into_int();
+                                if length < 0 {
+                                    let msg = format!("cannot keep negative number of frames of history: {length}");
+                                    return Err(CompilationError::InvalidArgument(msg, self.get_location(&Range::from(s))));
+                                }
+                                self.options.history = self.options.history.max(length as usize);
+                            }
+                            TopLevelOperation::LoadSubtitles => {
+                                let filepath = params[0].get_value(&self.globals).into_string().to_string();
+                                let path = self.file_manager.resolve(&filepath);
+                                let content = self.read_file(&path, &filepath, &Range::from(s))?;
+                                match Subtitles::parse(&content) {
+                                    Ok(subtitles) => self.options.subtitles = Some(subtitles),
+                                    Err(e) => return Err(CompilationError::InvalidFile(filepath, e, self.get_location(&Range::from(s)))),
+                                }
+                            }
+                            TopLevelOperation::LoadSubtitleFont => {
+                                let filepath = params[0].get_value(&self.globals).into_string().to_string();
+                                let path = self.file_manager.resolve(&filepath);
+                                let content = self.read_file(&path, &filepath, &Range::from(s))?;
+                                match Font::parse_bdf(&content) {
+                                    Ok(font) => self.options.subtitle_font = Some(font),
+                                    Err(e) => return Err(CompilationError::InvalidFile(filepath, e, self.get_location(&Range::from(s)))),
+                                }
+                            }
                         }
                     } else {
-                        sv.instantiate(params, &self.operations, &self.structures, &mut self.globals);
+                        self.check_arguments(&sv, &params, &Range::from(s))?;
+                        sv.instantiate(params, &mut self.get_context(), &self.operations, &self.structures, &mut self.globals);
                     }
                 }
             }
@@ -99,6 +142,84 @@ impl Parser {
         Ok(())
     }
 
+    /// `color name = value;`, the value can be any sequence returning a color.
+    fn parse_color_definition(&mut self, definition: &ast::ColorDefinition) -> Result<(), CompilationError> {
+        let (seq, params) = self.parse_sequence(&definition.value.0)?;
+        let Some(sv) = self.automaton.run(seq.get()) else {
+            return Err(CompilationError::UnknownSequence(seq, self.get_location(&definition.value.1)));
+        };
+        self.check_arguments(&sv, &params, &definition.value.1)?;
+        let value = sv.into_value(params, &mut self.get_context(), &self.operations, &self.structures, &mut self.globals);
+        if value.get_type() != VariableType::Color {
+            return Err(CompilationError::MismatchedType(VariableType::Color, value.get_type(), self.get_location(&definition.value.1)));
+        }
+        self.define_color(definition.name.0.clone(), value.into_color(), &definition.name.1)
+    }
+
+    /// Name all colors of a GIMP palette, the path is relative to the current file.
+    fn load_palette(&mut self, filepath: &str, range: &ast::Range) -> Result<(), CompilationError> {
+        let path = self.file_manager.resolve(filepath);
+        let content = self.read_file(&path, filepath, range)?;
+        let colors = color::parse_palette(&content)
+            .map_err(|e| CompilationError::InvalidFile(filepath.to_string(), e, self.get_location(range)))?;
+        for (name, color) in colors {
+            // a palette is not written for vinx, so names which do not fit are only skipped
+            match self.define_color(name, color, range) {
+                Err(CompilationError::InvalidColorName(name, loc) | CompilationError::ReservedColorName(name, loc))
+                    => self.warnings.push(Warning::SkippedPaletteColor(name, loc)),
+                result => result?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Read a file the program refers to as `filepath` (e.g. subtitles), `path` is where it is found.
+    fn read_file(&self, path: impl AsRef<std::path::Path>, filepath: &str, range: &ast::Range) -> Result<String, CompilationError> {
+        std::fs::read_to_string(path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => CompilationError::FileNotFound(filepath.to_string(), Some(self.get_location(range))),
+            _ => CompilationError::InvalidFile(filepath.to_string(), e.to_string(), self.get_location(range)),
+        })
+    }
+
+    /// Check literal arguments of a builtin operation, so that it does not fail only when running.
+    pub fn check_arguments(&self, sv: &SequenceValue, params: &[Variable], range: &ast::Range) -> Result<(), CompilationError> {
+        let SequenceValue::Operation(id) = sv else {
+            return Ok(());
+        };
+        let OperationTemplateEnum::Standard(op) = &self.operations[*id] else {
+            return Ok(());
+        };
+        op.check_arguments(params)
+            .map_err(|e| CompilationError::InvalidArgument(format!("{}: {e}", op.get_signature()), self.get_location(range)))
+    }
+
+    /// Name a color, so that it can be used as a keyword in the rest of the program.
+    fn define_color(&mut self, name: String, color: Color, range: &ast::Range) -> Result<(), CompilationError> {
+        let is_word = name.starts_with(|c: char| c.is_ascii_lowercase())
+            && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !is_word {
+            return Err(CompilationError::InvalidColorName(name, self.get_location(range)));
+        }
+        if self.automaton.is_keyword(&name) {
+            return Err(CompilationError::ReservedColorName(name, self.get_location(range)));
+        }
+        if self.colors.insert(name.clone(), color).is_some() || color::color_by_name(&name).is_some() {
+            self.warnings.push(Warning::RedefinedColor(name, self.get_location(range)));
+        }
+        Ok(())
+    }
+
+    /// Get a color by its name, colors defined by the program take precedence over the standard ones.
+    pub fn get_named_color(&self, name: &str) -> Option<Color> {
+        self.colors.get(name).copied().or_else(|| color::color_by_name(name))
+    }
+
+    /// Get a context for evaluating sequences during parsing.
+    /// It does not hold any frames, but it knows the video properties (e.g. `frame width`).
+    pub fn get_context(&self) -> Context<'static> {
+        Context::from_info(self.video_info)
+    }
+
     pub fn get_location(&self, range: &ast::Range) -> Location {
         Location::new(self.file_manager.current_file(), *range)
     }
@@ -110,11 +231,29 @@ impl Parser {
     }
 }
 
-pub fn parse(filepath: &str) -> Result<(Stack,Vec<Action>,Operations,Options), CompilationError> {
-    let mut it = Parser::new(filepath)?;
+pub fn parse(filepath: &str, video_info: Option<VideoInfo>) -> Result<(Stack,Vec<Action>,Operations,Options), CompilationError> {
+    let mut it = Parser::new(filepath, video_info)?;
     it.parse()?;
     for w in it.warnings.iter() {
         w.print();
     }
     Ok(it.get())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_files_next_to_the_program() {
+        // the program is not in the working directory, the files it loads are found next to it
+        let dir = std::env::temp_dir().join("vinx_test_files_next_to_the_program");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("subs.srt"), "1\n00:00:01,000 --> 00:00:02,000\nHello\n").unwrap();
+        std::fs::write(dir.join("main.vinx"), "load subtitles from \"subs.srt\";\n").unwrap();
+        let Ok((.., options)) = parse(dir.join("main.vinx").to_str().unwrap(), None) else {
+            panic!("the subtitles were not loaded");
+        };
+        assert_eq!(options.subtitles.unwrap().text_at(1000).unwrap(), "Hello");
+    }
+}
@@ -15,6 +15,7 @@ impl Parser {
     pub fn parse_value(&self, val: &ast::Value) -> Result<VariableValue, ValueParseError> {
         match val {
             ast::Value::Number(n) => Ok(VariableValue::Int(*n as i32)),
+            ast::Value::Float(x) => Ok(VariableValue::Float(*x)),
             ast::Value::Variable(name) => {
                 match self.globals.get_variable(name) {
                     Some(v) => Ok(v.clone()),
@@ -60,7 +61,15 @@ impl Parser {
         let mut params = vec![];
         for w in seq {
             match &w.0 {
-                ast::sequence::Word::Keyword(k) => words.push(Word::Keyword(k.clone())),
+                ast::sequence::Word::Keyword(k) => {
+                    // named colors are written as plain keywords, keywords of sequences take precedence
+                    if !self.automaton.is_keyword(k) && let Some(c) = self.get_named_color(k) {
+                        words.push(Word::Type(VariableType::Color));
+                        params.push(Variable::Static(VariableValue::Color(c)));
+                    } else {
+                        words.push(Word::Keyword(k.clone()));
+                    }
+                }
                 ast::sequence::Word::Value(v) => {
                     let var = match self.parse_value_as_variable(v) {
                         Ok(v) => v,
@@ -84,12 +93,19 @@ impl Parser {
     pub fn parse_type(&self, typ: &ast::Type) -> Result<VariableType, CompilationError> {
         let mut t = match typ.value.as_ref() {
             "Int" => VariableType::Int,
+            "Float" => VariableType::Float,
+            "Bool" => VariableType::Bool,
             "Pos" => VariableType::Pos,
             "Column" => VariableType::Column,
             "Row" => VariableType::Row,
             "Color" => VariableType::Color,
             "String" => VariableType::String,
             "Effect" => VariableType::Effect,
+            "Gradient" => VariableType::Gradient,
+            "Mask" => VariableType::Mask,
+            "Transition" => VariableType::Transition,
+            "Sprite" => VariableType::Sprite,
+            "Emitter" => VariableType::Emitter,
             "Direction" => VariableType::Direction,
             "Rectangle" => VariableType::Rectangle,
             "Image" => VariableType::Image,
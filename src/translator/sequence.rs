@@ -52,20 +52,19 @@ impl SequenceValue {
         }
     }
 
-    pub fn into_value(self, params: Vec<Variable>, operations: &Operations, structures: &Vec<StructureTemplate>, stack: &mut Stack) -> VariableValue {
-        self.instantiate(params, operations, structures, stack).expect("error: expected a value")
+    pub fn into_value(self, params: Vec<Variable>, context: &mut Context, operations: &Operations, structures: &Vec<StructureTemplate>, stack: &mut Stack) -> VariableValue {
+        self.instantiate(params, context, operations, structures, stack).expect("error: expected a value")
     }
 
-    pub fn instantiate(self, params: Vec<Variable>, operations: &Operations, structures: &Vec<StructureTemplate>, stack: &mut Stack) -> Option<VariableValue> {
-        let mut context = Context::new();
+    pub fn instantiate(self, params: Vec<Variable>, context: &mut Context, operations: &Operations, structures: &Vec<StructureTemplate>, stack: &mut Stack) -> Option<VariableValue> {
         match self {
             SequenceValue::Structure(id) => {
-                Some(VariableValue::Structure(structures[id].instantiate(params, &mut context, operations, structures, stack)))
+                Some(VariableValue::Structure(structures[id].instantiate(params, context, operations, structures, stack)))
             }
             SequenceValue::Operation(id) => {
                 let op = operations[id].get();
                 op.instantiate(params)
-                    .process(&mut context, stack, &mut vec![], operations)
+                    .process(context, stack, &mut vec![], operations)
             }
         }
     }
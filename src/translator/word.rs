@@ -62,10 +62,17 @@ impl Display for Word {
 macro_rules! word {
     ( [ $($x:tt)+ ] ) => { Word::Type(VariableType::Vec(Box::new(vtype!($($x)+)))) };
     ( Int ) => { Word::Type(VariableType::Int) };
+    ( Float ) => { Word::Type(VariableType::Float) };
+    ( Bool ) => { Word::Type(VariableType::Bool) };
     ( Pos ) => { Word::Type(VariableType::Pos) };
     ( Color ) => { Word::Type(VariableType::Color) };
     ( Direction ) => { Word::Type(VariableType::Direction) };
     ( Effect ) => { Word::Type(VariableType::Effect) };
+    ( Gradient ) => { Word::Type(VariableType::Gradient) };
+    ( Mask ) => { Word::Type(VariableType::Mask) };
+    ( Transition ) => { Word::Type(VariableType::Transition) };
+    ( Sprite ) => { Word::Type(VariableType::Sprite) };
+    ( Emitter ) => { Word::Type(VariableType::Emitter) };
     ( Image ) => { Word::Type(VariableType::Image) };
     ( Structure ( $i:expr ) ) => { Word::Type(VariableType::Structure($i)) };
     ( Any ( $i:expr ) ) => { Word::Type(VariableType::Any($i)) };
@@ -86,6 +93,7 @@ mod tests {
     #[test]
     fn test_macro() {
         assert_eq!(word!(Int),Word::Type(VariableType::Int));
+        assert_eq!(word!(Float),Word::Type(VariableType::Float));
         assert_eq!(word!(Pos),Word::Type(VariableType::Pos));
         assert_eq!(word!(Color),Word::Type(VariableType::Color));
         assert_eq!(word!(Direction),Word::Type(VariableType::Direction));
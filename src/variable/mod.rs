@@ -6,4 +6,4 @@ mod value;
 pub use stack::{Scope,Stack};
 pub use types::VariableType;
 pub use variable::Variable;
-pub use value::{VariableValue,Direction,Structure,Effect,Color,Position,Rectangle,Column,Row};
+pub use value::{VariableValue,Direction,Structure,Effect,Gradient,Mask,Transition,Sprite,Look,Edges,Emitter,Color,Position,Rectangle,Column,Row,color};
@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use crate::{variable::{Column, Row, value::{Position, Rectangle}}, video::Frame};
+use crate::{variable::{Column, Row, value::{Position, Rectangle}}, video::Image};
 
 use super::{Structure, Direction, VariableValue};
 
@@ -15,10 +15,17 @@ macro_rules! vtype {
     ( [ $($x:tt)+ ] ) => { VariableType::Vec(Box::new(vtype!($($x)+))) };
     ( String ) => { VariableType::String };
     ( Int ) => { VariableType::Int };
+    ( Float ) => { VariableType::Float };
+    ( Bool ) => { VariableType::Bool };
     ( Pos ) => { VariableType::Pos };
     ( Color ) => { VariableType::Color };
     ( Direction ) => { VariableType::Direction };
     ( Effect ) => { VariableType::Effect };
+    ( Gradient ) => { VariableType::Gradient };
+    ( Mask ) => { VariableType::Mask };
+    ( Transition ) => { VariableType::Transition };
+    ( Sprite ) => { VariableType::Sprite };
+    ( Emitter ) => { VariableType::Emitter };
     ( Column ) => { VariableType::Column };
     ( Row ) => { VariableType::Row };
     ( Image ) => { VariableType::Image };
@@ -31,12 +38,19 @@ macro_rules! vtype {
 #[derive(Hash,Clone, Debug, Eq)]
 pub enum VariableType {
     Int,
+    Float,
+    Bool,
     Pos,
     Column,
     Row,
     Color,
     String,
     Effect,
+    Gradient,
+    Mask,
+    Transition,
+    Sprite,
+    Emitter,
     Direction,
     Rectangle,
     Image,
@@ -53,11 +67,18 @@ impl Display for VariableType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             VariableType::Int => write!(f, "Int"),
+            VariableType::Float => write!(f, "Float"),
+            VariableType::Bool => write!(f, "Bool"),
             VariableType::Pos => write!(f, "Pos"),
             VariableType::Column => write!(f, "Column"),
             VariableType::Row => write!(f, "Row"),
             VariableType::Color => write!(f, "Color"),
             VariableType::Effect => write!(f, "Effect"),
+            VariableType::Gradient => write!(f, "Gradient"),
+            VariableType::Mask => write!(f, "Mask"),
+            VariableType::Transition => write!(f, "Transition"),
+            VariableType::Sprite => write!(f, "Sprite"),
+            VariableType::Emitter => write!(f, "Emitter"),
             VariableType::Direction => write!(f, "Dir"),
             VariableType::String => write!(f, "Str"),
             VariableType::Rectangle => write!(f, "Rectangle"),
@@ -104,15 +125,22 @@ impl VariableType {
         match &self {
             VariableType::Vec(x) => VariableValue::Vec(vec![x.default().to_var()]),
             VariableType::Int => VariableValue::Int(0),
+            VariableType::Float => VariableValue::Float(0.0),
+            VariableType::Bool => VariableValue::Bool(false),
             VariableType::Pos => VariableValue::Pos(Position::default()),
             VariableType::Column => VariableValue::Column(Column::default()),
             VariableType::Row => VariableValue::Row(Row::default()),
             VariableType::Direction => VariableValue::Direction(Direction::Left),
             VariableType::Color => VariableValue::Color([0,0,0].into()),
             VariableType::String => VariableValue::String("".to_string()),
-            VariableType::Image => VariableValue::Image(Frame::new(0,0)),
+            VariableType::Image => VariableValue::Image(Image::new(0,0)),
             VariableType::Rectangle => VariableValue::Rectangle(Rectangle::default()),
             VariableType::Effect => VariableValue::Effect(super::Effect::Blur),
+            VariableType::Gradient => VariableValue::Gradient(super::Gradient::default()),
+            VariableType::Mask => VariableValue::Mask(super::Mask::default()),
+            VariableType::Transition => VariableValue::Transition(super::Transition::default()),
+            VariableType::Sprite => VariableValue::Sprite(super::Sprite::default()),
+            VariableType::Emitter => VariableValue::Emitter(super::Emitter::default()),
             VariableType::Any(x) => VariableValue::Any(*x),
             VariableType::Structure(x) => VariableValue::Structure(Structure::default(*x)),
             VariableType::SelfReference => VariableValue::SelfReference,
@@ -327,6 +355,11 @@ mod tests {
         let v1 = vtype!(Int);
         let v2 = vtype!(Int);
         assert_eq!(v1,v2);
+        // Float,
+        let v1 = vtype!(Float);
+        let v2 = vtype!(Float);
+        assert_eq!(v1,v2);
+        assert_ne!(v1,vtype!(Int));
         // Pos,
         let v1 = vtype!(Pos);
         let v2 = vtype!(Pos);
@@ -343,6 +376,30 @@ mod tests {
         let v1 = vtype!(Effect);
         let v2 = vtype!(Effect);
         assert_eq!(v1,v2);
+        // Gradient,
+        let v1 = vtype!(Gradient);
+        let v2 = vtype!(Gradient);
+        assert_eq!(v1,v2);
+        assert_ne!(v1,vtype!(Color));
+        // Mask,
+        let v1 = vtype!(Mask);
+        let v2 = vtype!(Mask);
+        assert_eq!(v1,v2);
+        assert_ne!(v1,vtype!(Image));
+        // Transition,
+        let v1 = vtype!(Transition);
+        let v2 = vtype!(Transition);
+        assert_eq!(v1,v2);
+        // Sprite,
+        let v1 = vtype!(Sprite);
+        let v2 = vtype!(Sprite);
+        assert_eq!(v1,v2);
+        assert_ne!(v1,vtype!(Image));
+        // Emitter,
+        let v1 = vtype!(Emitter);
+        let v2 = vtype!(Emitter);
+        assert_eq!(v1,v2);
+        assert_ne!(v1,vtype!(Sprite));
         // Direction,
         let v1 = vtype!(Direction);
         let v2 = vtype!(Direction);
@@ -0,0 +1,299 @@
+use super::Color;
+
+/// Standard CSS/X11 color names.
+const NAMED_COLORS: &[(&str, [u8; 3])] = &[
+    ("aliceblue", [240, 248, 255]),
+    ("antiquewhite", [250, 235, 215]),
+    ("aqua", [0, 255, 255]),
+    ("aquamarine", [127, 255, 212]),
+    ("azure", [240, 255, 255]),
+    ("beige", [245, 245, 220]),
+    ("bisque", [255, 228, 196]),
+    ("black", [0, 0, 0]),
+    ("blanchedalmond", [255, 235, 205]),
+    ("blue", [0, 0, 255]),
+    ("blueviolet", [138, 43, 226]),
+    ("brown", [165, 42, 42]),
+    ("burlywood", [222, 184, 135]),
+    ("cadetblue", [95, 158, 160]),
+    ("chartreuse", [127, 255, 0]),
+    ("chocolate", [210, 105, 30]),
+    ("coral", [255, 127, 80]),
+    ("cornflowerblue", [100, 149, 237]),
+    ("cornsilk", [255, 248, 220]),
+    ("crimson", [220, 20, 60]),
+    ("cyan", [0, 255, 255]),
+    ("darkblue", [0, 0, 139]),
+    ("darkcyan", [0, 139, 139]),
+    ("darkgoldenrod", [184, 134, 11]),
+    ("darkgray", [169, 169, 169]),
+    ("darkgreen", [0, 100, 0]),
+    ("darkgrey", [169, 169, 169]),
+    ("darkkhaki", [189, 183, 107]),
+    ("darkmagenta", [139, 0, 139]),
+    ("darkolivegreen", [85, 107, 47]),
+    ("darkorange", [255, 140, 0]),
+    ("darkorchid", [153, 50, 204]),
+    ("darkred", [139, 0, 0]),
+    ("darksalmon", [233, 150, 122]),
+    ("darkseagreen", [143, 188, 143]),
+    ("darkslateblue", [72, 61, 139]),
+    ("darkslategray", [47, 79, 79]),
+    ("darkslategrey", [47, 79, 79]),
+    ("darkturquoise", [0, 206, 209]),
+    ("darkviolet", [148, 0, 211]),
+    ("deeppink", [255, 20, 147]),
+    ("deepskyblue", [0, 191, 255]),
+    ("dimgray", [105, 105, 105]),
+    ("dimgrey", [105, 105, 105]),
+    ("dodgerblue", [30, 144, 255]),
+    ("firebrick", [178, 34, 34]),
+    ("floralwhite", [255, 250, 240]),
+    ("forestgreen", [34, 139, 34]),
+    ("fuchsia", [255, 0, 255]),
+    ("gainsboro", [220, 220, 220]),
+    ("ghostwhite", [248, 248, 255]),
+    ("gold", [255, 215, 0]),
+    ("goldenrod", [218, 165, 32]),
+    ("gray", [128, 128, 128]),
+    ("green", [0, 128, 0]),
+    ("greenyellow", [173, 255, 47]),
+    ("grey", [128, 128, 128]),
+    ("honeydew", [240, 255, 240]),
+    ("hotpink", [255, 105, 180]),
+    ("indianred", [205, 92, 92]),
+    ("indigo", [75, 0, 130]),
+    ("ivory", [255, 255, 240]),
+    ("khaki", [240, 230, 140]),
+    ("lavender", [230, 230, 250]),
+    ("lavenderblush", [255, 240, 245]),
+    ("lawngreen", [124, 252, 0]),
+    ("lemonchiffon", [255, 250, 205]),
+    ("lightblue", [173, 216, 230]),
+    ("lightcoral", [240, 128, 128]),
+    ("lightcyan", [224, 255, 255]),
+    ("lightgoldenrodyellow", [250, 250, 210]),
+    ("lightgray", [211, 211, 211]),
+    ("lightgreen", [144, 238, 144]),
+    ("lightgrey", [211, 211, 211]),
+    ("lightpink", [255, 182, 193]),
+    ("lightsalmon", [255, 160, 122]),
+    ("lightseagreen", [32, 178, 170]),
+    ("lightskyblue", [135, 206, 250]),
+    ("lightslategray", [119, 136, 153]),
+    ("lightslategrey", [119, 136, 153]),
+    ("lightsteelblue", [176, 196, 222]),
+    ("lightyellow", [255, 255, 224]),
+    ("lime", [0, 255, 0]),
+    ("limegreen", [50, 205, 50]),
+    ("linen", [250, 240, 230]),
+    ("magenta", [255, 0, 255]),
+    ("maroon", [128, 0, 0]),
+    ("mediumaquamarine", [102, 205, 170]),
+    ("mediumblue", [0, 0, 205]),
+    ("mediumorchid", [186, 85, 211]),
+    ("mediumpurple", [147, 112, 219]),
+    ("mediumseagreen", [60, 179, 113]),
+    ("mediumslateblue", [123, 104, 238]),
+    ("mediumspringgreen", [0, 250, 154]),
+    ("mediumturquoise", [72, 209, 204]),
+    ("mediumvioletred", [199, 21, 133]),
+    ("midnightblue", [25, 25, 112]),
+    ("mintcream", [245, 255, 250]),
+    ("mistyrose", [255, 228, 225]),
+    ("moccasin", [255, 228, 181]),
+    ("navajowhite", [255, 222, 173]),
+    ("navy", [0, 0, 128]),
+    ("oldlace", [253, 245, 230]),
+    ("olive", [128, 128, 0]),
+    ("olivedrab", [107, 142, 35]),
+    ("orange", [255, 165, 0]),
+    ("orangered", [255, 69, 0]),
+    ("orchid", [218, 112, 214]),
+    ("palegoldenrod", [238, 232, 170]),
+    ("palegreen", [152, 251, 152]),
+    ("paleturquoise", [175, 238, 238]),
+    ("palevioletred", [219, 112, 147]),
+    ("papayawhip", [255, 239, 213]),
+    ("peachpuff", [255, 218, 185]),
+    ("peru", [205, 133, 63]),
+    ("pink", [255, 192, 203]),
+    ("plum", [221, 160, 221]),
+    ("powderblue", [176, 224, 230]),
+    ("purple", [128, 0, 128]),
+    ("rebeccapurple", [102, 51, 153]),
+    ("red", [255, 0, 0]),
+    ("rosybrown", [188, 143, 143]),
+    ("royalblue", [65, 105, 225]),
+    ("saddlebrown", [139, 69, 19]),
+    ("salmon", [250, 128, 114]),
+    ("sandybrown", [244, 164, 96]),
+    ("seagreen", [46, 139, 87]),
+    ("seashell", [255, 245, 238]),
+    ("sienna", [160, 82, 45]),
+    ("silver", [192, 192, 192]),
+    ("skyblue", [135, 206, 235]),
+    ("slateblue", [106, 90, 205]),
+    ("slategray", [112, 128, 144]),
+    ("slategrey", [112, 128, 144]),
+    ("snow", [255, 250, 250]),
+    ("springgreen", [0, 255, 127]),
+    ("steelblue", [70, 130, 180]),
+    ("tan", [210, 180, 140]),
+    ("teal", [0, 128, 128]),
+    ("thistle", [216, 191, 216]),
+    ("tomato", [255, 99, 71]),
+    ("turquoise", [64, 224, 208]),
+    ("violet", [238, 130, 238]),
+    ("wheat", [245, 222, 179]),
+    ("white", [255, 255, 255]),
+    ("whitesmoke", [245, 245, 245]),
+    ("yellow", [255, 255, 0]),
+    ("yellowgreen", [154, 205, 50]),
+];
+
+/// Look up a standard (CSS/X11) color by its name.
+pub fn color_by_name(name: &str) -> Option<Color> {
+    NAMED_COLORS.binary_search_by_key(&name, |(n, _)| n)
+        .ok()
+        .map(|i| Color::from(NAMED_COLORS[i].1))
+}
+
+/// Perceived brightness of a color (0.0 - 255.0), using the Rec. 601 luma weights.
+pub fn luminance(c: Color) -> f64 {
+    0.299 * c[0] as f64 + 0.587 * c[1] as f64 + 0.114 * c[2] as f64
+}
+
+/// Mix two colors, `ratio` is the weight of the second color (0.0 - 1.0).
+pub fn mix(c1: Color, c2: Color, ratio: f64) -> Color {
+    let ratio = ratio.clamp(0.0, 1.0);
+    let channel = |a: u8, b: u8| (a as f64 * (1.0 - ratio) + b as f64 * ratio).round() as u8;
+    Color::from([channel(c1[0], c2[0]), channel(c1[1], c2[1]), channel(c1[2], c2[2])])
+}
+
+/// Build a color from hue (in degrees), saturation and value (both 0.0 - 1.0).
+pub fn from_hsv(hue: f64, saturation: f64, value: f64) -> Color {
+    let h = hue.rem_euclid(360.0) / 60.0;
+    let s = saturation.clamp(0.0, 1.0);
+    let v = value.clamp(0.0, 1.0);
+    let c = v * s;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = v - c;
+    let channel = |f: f64| ((f + m) * 255.0).round() as u8;
+    Color::from([channel(r), channel(g), channel(b)])
+}
+
+/// Get hue (in degrees), saturation and value (both 0.0 - 1.0) of a color.
+pub fn to_hsv(c: Color) -> (f64, f64, f64) {
+    let [r, g, b] = c.0.map(|x| x as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+    (hue, saturation, max)
+}
+
+/// Read the named colors of a GIMP palette (`.gpl`).
+/// Names are turned into color names of the language: `Brand Blue` becomes `brand_blue` and
+/// `Grey 50%` becomes `grey_50`, entries without a name are skipped.
+pub fn parse_palette(content: &str) -> Result<Vec<(String, Color)>, String> {
+    let mut lines = content.lines().enumerate();
+    if lines.next().map(|(_, l)| l.trim()) != Some("GIMP Palette") {
+        return Err("missing `GIMP Palette` header".to_string());
+    }
+    let mut colors = vec![];
+    for (i, line) in lines {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("Name:") || line.starts_with("Columns:") {
+            continue;
+        }
+        let mut it = line.split_whitespace();
+        let mut channel = || -> Result<u8, String> {
+            it.next()
+                .and_then(|x| x.parse().ok())
+                .ok_or_else(|| format!("line {}: expected three channel values (0-255)", i + 1))
+        };
+        let c = Color::from([channel()?, channel()?, channel()?]);
+        let name = palette_color_name(&it.collect::<Vec<_>>().join(" "));
+        if name.is_empty() || name == "untitled" {
+            continue;
+        }
+        colors.push((name, c));
+    }
+    Ok(colors)
+}
+
+/// Lowercase the name and join its words (runs of letters and digits) with `_`.
+fn palette_color_name(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_by_name() {
+        assert!(NAMED_COLORS.windows(2).all(|w| w[0].0 < w[1].0), "color table has to be sorted");
+        assert_eq!(color_by_name("red"), Some(Color::from([255, 0, 0])));
+        assert_eq!(color_by_name("rebeccapurple"), Some(Color::from([102, 51, 153])));
+        assert_eq!(color_by_name("notacolor"), None);
+    }
+
+    #[test]
+    fn test_mix() {
+        let black = Color::from([0, 0, 0]);
+        let white = Color::from([255, 255, 255]);
+        assert_eq!(mix(black, white, 0.0), black);
+        assert_eq!(mix(black, white, 1.0), white);
+        assert_eq!(mix(black, white, 0.5), Color::from([128, 128, 128]));
+    }
+
+    #[test]
+    fn test_hsv() {
+        assert_eq!(from_hsv(0.0, 1.0, 1.0), Color::from([255, 0, 0]));
+        assert_eq!(from_hsv(120.0, 1.0, 1.0), Color::from([0, 255, 0]));
+        assert_eq!(from_hsv(240.0, 1.0, 0.5), Color::from([0, 0, 128]));
+        let (h, s, v) = to_hsv(Color::from([0, 0, 255]));
+        assert_eq!((h, s, v), (240.0, 1.0, 1.0));
+        let c = Color::from([26, 115, 232]);
+        let (h, s, v) = to_hsv(c);
+        assert_eq!(from_hsv(h, s, v), c);
+    }
+
+    #[test]
+    fn test_parse_palette() {
+        let palette = "GIMP Palette\nName: Brand\nColumns: 2\n# comment\n 26 115 232\tBrand Blue\n255 255 255\tpaper-white\n0 0 0\tUntitled\n1 2 3\n\
+            128 128 128\tGrey 50%\n0 0 139\tBlue (dark)\n";
+        assert_eq!(parse_palette(palette), Ok(vec![
+            ("brand_blue".to_string(), Color::from([26, 115, 232])),
+            ("paper_white".to_string(), Color::from([255, 255, 255])),
+            ("grey_50".to_string(), Color::from([128, 128, 128])),
+            ("blue_dark".to_string(), Color::from([0, 0, 139])),
+        ]));
+        assert!(parse_palette("255 0 0 red").is_err());
+        assert!(parse_palette("GIMP Palette\n300 0 0 red").is_err());
+        assert!(parse_palette("GIMP Palette\n255 0 red").is_err());
+    }
+}
@@ -0,0 +1,65 @@
+/// What happens when a moving object reaches an edge of the frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edges {
+    /// The object may leave the frame.
+    Ignore,
+    /// The object stops at the edge.
+    Clamp,
+    /// The object comes back from the opposite edge.
+    Wrap,
+    /// The object is reflected back and its velocity flips.
+    Bounce,
+}
+
+impl Edges {
+    /// Keep an object spanning `[start, start + size)` on one axis within `[0, limit)`,
+    /// `velocity` is the last movement of the object on the axis.
+    pub fn constrain(&self, start: &mut f64, size: f64, velocity: &mut f64, limit: f64) {
+        match self {
+            Edges::Ignore => {}
+            Edges::Clamp => *start = start.min(limit - size).max(0.0),
+            Edges::Wrap => {
+                // rounding makes `rem_euclid` return `limit` for tiny negative values
+                *start = start.rem_euclid(limit);
+                if *start >= limit {
+                    *start = 0.0;
+                }
+            }
+            Edges::Bounce => {
+                // the part which went over the edge is reflected back
+                if *start < 0.0 && *velocity < 0.0 {
+                    *start = -*start;
+                    *velocity = -*velocity;
+                } else if *start + size > limit && *velocity > 0.0 {
+                    *start -= 2.0 * (*start + size - limit);
+                    *velocity = -*velocity;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn constrain(edges: Edges, start: f64, size: f64, velocity: f64) -> (f64, f64) {
+        let (mut start, mut velocity) = (start, velocity);
+        edges.constrain(&mut start, size, &mut velocity, 10.0);
+        (start, velocity)
+    }
+
+    #[test]
+    fn test_constrain() {
+        assert_eq!(constrain(Edges::Ignore, -3.0, 1.0, -4.0), (-3.0, -4.0));
+        assert_eq!(constrain(Edges::Clamp, -3.0, 1.0, -4.0), (0.0, -4.0));
+        assert_eq!(constrain(Edges::Clamp, 12.0, 1.0, 4.0), (9.0, 4.0));
+        assert_eq!(constrain(Edges::Wrap, -3.0, 1.0, -4.0), (7.0, -4.0));
+        assert_eq!(constrain(Edges::Wrap, 12.0, 1.0, 4.0), (2.0, 4.0));
+        assert_eq!(constrain(Edges::Wrap, -1e-20, 1.0, -4.0), (0.0, -4.0));
+        assert_eq!(constrain(Edges::Bounce, -3.0, 1.0, -4.0), (3.0, 4.0));
+        assert_eq!(constrain(Edges::Bounce, 8.0, 4.0, 4.0), (4.0, -4.0));
+        // already moving back, it is not flipped again
+        assert_eq!(constrain(Edges::Bounce, 8.0, 4.0, -4.0), (8.0, -4.0));
+    }
+}
@@ -1,10 +1,95 @@
 use std::fmt::Display;
 
-#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+use super::{Color, color};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Effect {
     Blur,
     Random,
     Inverse,
+    Sharpen,
+    Emboss,
+    /// Sobel edge detection.
+    EdgeDetect,
+    /// Replace blocks of the given size by their average color.
+    Pixelate(i32),
+    /// Like `Pixelate`, with darker gaps between the blocks, so they look like tiles.
+    Mosaic(i32),
+    /// Add to all channels (negative values darken).
+    Brightness(i32),
+    /// Scale the distance from middle gray, in percent (100 keeps the colors).
+    Contrast(i32),
+    /// Scale the distance from the gray of the same brightness, in percent (0 is grayscale).
+    Saturation(i32),
+    /// Rotate the hue, in degrees.
+    HueShift(i32),
+    /// Gamma correction, values above 1 brighten dark parts.
+    Gamma(f64),
+    Grayscale,
+    Sepia,
+    /// Pixels at least this bright become white, the rest black.
+    Threshold(i32),
+    /// Reduce each channel to the number of levels.
+    Posterize(i32),
+    /// Each output channel is a weighted sum of the input channels, weights in percent.
+    ChannelMix([[i32; 3]; 3]),
+}
+
+impl Effect {
+    /// Whether the effect changes each pixel on its own, so it is done by `grade`.
+    pub fn is_per_pixel(&self) -> bool {
+        !matches!(self, Effect::Blur | Effect::Random | Effect::Sharpen | Effect::Emboss | Effect::EdgeDetect
+            | Effect::Pixelate(_) | Effect::Mosaic(_))
+    }
+
+    /// Apply a per-pixel effect to a single color.
+    pub fn grade(&self, c: Color) -> Color {
+        let channels = c.0.map(|x| x as f64);
+        let to_color = |c: [f64; 3]| Color::from(c.map(|x| x.round().clamp(0.0, 255.0) as u8));
+        match *self {
+            Effect::Blur | Effect::Random | Effect::Sharpen | Effect::Emboss | Effect::EdgeDetect
+                | Effect::Pixelate(_) | Effect::Mosaic(_) => panic!("error: effect {self} does not work per pixel"),
+            Effect::Inverse => Color::from(c.0.map(|x| 255 - x)),
+            Effect::Brightness(n) => to_color(channels.map(|x| x + n as f64)),
+            Effect::Contrast(p) => to_color(channels.map(|x| (x - 128.0) * p as f64 / 100.0 + 128.0)),
+            Effect::Saturation(p) => {
+                let l = color::luminance(c);
+                to_color(channels.map(|x| l + (x - l) * p as f64 / 100.0))
+            }
+            Effect::HueShift(d) => {
+                let (h, s, v) = color::to_hsv(c);
+                color::from_hsv(h + d as f64, s, v)
+            }
+            Effect::Gamma(g) => {
+                let g = g.max(f64::EPSILON);
+                to_color(channels.map(|x| 255.0 * (x / 255.0).powf(1.0 / g)))
+            }
+            Effect::Grayscale => {
+                let l = color::luminance(c);
+                to_color([l, l, l])
+            }
+            Effect::Sepia => {
+                let [r, g, b] = channels;
+                to_color([
+                    0.393 * r + 0.769 * g + 0.189 * b,
+                    0.349 * r + 0.686 * g + 0.168 * b,
+                    0.272 * r + 0.534 * g + 0.131 * b,
+                ])
+            }
+            Effect::Threshold(n) => {
+                let v = if color::luminance(c) >= n as f64 { 255 } else { 0 };
+                Color::from([v, v, v])
+            }
+            Effect::Posterize(n) => {
+                let steps = (n.max(2) - 1) as f64;
+                to_color(channels.map(|x| (x / 255.0 * steps).round() * 255.0 / steps))
+            }
+            Effect::ChannelMix(m) => {
+                let mix = |row: [i32; 3]| (0..3).map(|j| row[j] as f64 * channels[j] / 100.0).sum();
+                to_color([mix(m[0]), mix(m[1]), mix(m[2])])
+            }
+        }
+    }
 }
 
 impl Display for Effect {
@@ -13,6 +98,43 @@ impl Display for Effect {
             Self::Blur => write!(f, "blur"),
             Self::Random => write!(f, "random"),
             Self::Inverse => write!(f, "inverse"),
+            Self::Sharpen => write!(f, "sharpened"),
+            Self::Emboss => write!(f, "embossed"),
+            Self::EdgeDetect => write!(f, "edge detected"),
+            Self::Pixelate(n) => write!(f, "pixelated by {n}"),
+            Self::Mosaic(n) => write!(f, "mosaic by {n}"),
+            Self::Brightness(n) => write!(f, "brightened by {n}"),
+            Self::Contrast(p) => write!(f, "contrasted by {p} percent"),
+            Self::Saturation(p) => write!(f, "saturated by {p} percent"),
+            Self::HueShift(d) => write!(f, "hue shifted by {d}"),
+            Self::Gamma(g) => write!(f, "gamma corrected by {g}"),
+            Self::Grayscale => write!(f, "grayscaled"),
+            Self::Sepia => write!(f, "sepia toned"),
+            Self::Threshold(n) => write!(f, "thresholded at {n}"),
+            Self::Posterize(n) => write!(f, "posterized to {n} levels"),
+            Self::ChannelMix(m) => write!(f, "channels mixed by {m:?}"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grade() {
+        let c = Color::from([200, 100, 50]);
+        assert_eq!(Effect::Inverse.grade(c), Color::from([55, 155, 205]));
+        assert_eq!(Effect::Brightness(100).grade(c), Color::from([255, 200, 150]));
+        assert_eq!(Effect::Contrast(100).grade(c), c);
+        assert_eq!(Effect::Contrast(0).grade(c), Color::from([128, 128, 128]));
+        assert_eq!(Effect::Saturation(100).grade(c), c);
+        assert_eq!(Effect::Saturation(0).grade(c), Effect::Grayscale.grade(c));
+        assert_eq!(Effect::HueShift(360).grade(c), c);
+        assert_eq!(Effect::Gamma(1.0).grade(c), c);
+        assert_eq!(Effect::Threshold(128).grade(c), Color::from([0, 0, 0]));
+        assert_eq!(Effect::Posterize(2).grade(c), Color::from([255, 0, 0]));
+        let swap = [[0, 0, 100], [0, 100, 0], [100, 0, 0]];
+        assert_eq!(Effect::ChannelMix(swap).grade(c), Color::from([50, 100, 200]));
+    }
+}
@@ -0,0 +1,203 @@
+use std::fmt::Display;
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use super::{Color, Position, Rectangle, color};
+use crate::video::{Canvas, raster::Shape};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Particle {
+    position: (f64, f64),
+    velocity: (f64, f64),
+    age: u32,
+}
+
+/// Particle system, the runtime emits, moves and draws its particles every frame.
+/// Particles are random, but the same seed always gives the same particles.
+#[derive(Debug, Clone)]
+pub struct Emitter {
+    /// Where new particles appear.
+    pub position: Position,
+    /// New particles appear anywhere in the area instead of at `position`.
+    pub area: Option<Rectangle>,
+    /// Number of particles emitted per frame.
+    pub rate: u32,
+    /// Number of frames a particle lives.
+    pub lifetime: u32,
+    /// Initial velocity of particles (pixels per frame).
+    pub velocity: (f64, f64),
+    /// Maximal random change of the initial velocity on each axis.
+    pub spread: f64,
+    /// Added to the vertical velocity of particles every frame.
+    pub gravity: f64,
+    /// Color of particles at the start and at the end of their life.
+    pub colors: (Color, Color),
+    /// Radius of particles at the start and at the end of their life.
+    pub sizes: (f64, f64),
+    /// Whether new particles are emitted, existing particles live on either way.
+    pub emitting: bool,
+    /// Layer the particles are drawn on, `None` is the frame itself.
+    pub layer: Option<String>,
+    seed: u64,
+    /// Boxed, the generator state is large compared to other values.
+    rng: Box<StdRng>,
+    particles: Vec<Particle>,
+}
+
+impl Emitter {
+    pub fn new(position: Position, rate: u32) -> Self {
+        Self {
+            position,
+            area: None,
+            rate,
+            lifetime: 30,
+            velocity: (0.0, 0.0),
+            spread: 1.0,
+            gravity: 0.0,
+            colors: (Color::from([255, 255, 255]), Color::from([255, 255, 255])),
+            sizes: (1.0, 1.0),
+            emitting: true,
+            layer: None,
+            seed: 0,
+            rng: Box::new(StdRng::seed_from_u64(0)),
+            particles: vec![],
+        }
+    }
+
+    pub fn default() -> Self {
+        Self::new(Position::default(), 0)
+    }
+
+    /// Restart the random generator, existing particles are kept.
+    pub fn seed(&mut self, seed: u64) {
+        self.seed = seed;
+        *self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    pub fn particle_count(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// Age and move the particles, remove the dead ones and emit new ones.
+    pub fn step(&mut self) {
+        let (gravity, lifetime) = (self.gravity, self.lifetime);
+        self.particles.retain_mut(|p| {
+            p.age += 1;
+            p.velocity.1 += gravity;
+            p.position = (p.position.0 + p.velocity.0, p.position.1 + p.velocity.1);
+            p.age < lifetime
+        });
+        if !self.emitting || lifetime == 0 {
+            return;
+        }
+        for _ in 0..self.rate {
+            let position = match self.area {
+                Some(r) => {
+                    let ((l, t), (r, b)) = (r.top_left.exact(), r.bot_right.exact());
+                    (self.random_between(l, r), self.random_between(t, b))
+                }
+                None => self.position.exact(),
+            };
+            let spread = self.spread;
+            let velocity = (
+                self.velocity.0 + self.random_between(-spread, spread),
+                self.velocity.1 + self.random_between(-spread, spread),
+            );
+            self.particles.push(Particle { position, velocity, age: 0 });
+        }
+    }
+
+    fn random_between(&mut self, from: f64, to: f64) -> f64 {
+        if from >= to {
+            return from;
+        }
+        self.rng.gen_range(from..to)
+    }
+
+    /// Draw the particles, older particles are drawn first.
+    /// Particles are cut off at the edges of the canvas, the ones outside of it are skipped.
+    pub fn draw<C: Canvas>(&self, canvas: &mut C, antialias: bool) {
+        for p in &self.particles {
+            let life = p.age as f64 / self.lifetime.max(1) as f64;
+            let c = color::mix(self.colors.0, self.colors.1, life);
+            let radius = self.sizes.0 + (self.sizes.1 - self.sizes.0) * life;
+            if radius <= 0.0 {
+                continue;
+            }
+            let circle = Shape::Ellipse { center: p.position, radii: (radius, radius), outline: None };
+            let (l, t, r, b) = circle.bounds_within(canvas.size());
+            if l == r || t == b {
+                continue;
+            }
+            canvas.fill_shape(&circle, |_, _| c, antialias);
+        }
+    }
+}
+
+impl PartialEq for Emitter {
+    /// Emitters are equal when they have the same settings and particles,
+    /// the state of the random generator is not compared.
+    fn eq(&self, other: &Self) -> bool {
+        self.position == other.position && self.area == other.area && self.rate == other.rate
+            && self.lifetime == other.lifetime && self.velocity == other.velocity && self.spread == other.spread
+            && self.gravity == other.gravity && self.colors == other.colors && self.sizes == other.sizes
+            && self.emitting == other.emitting && self.layer == other.layer && self.seed == other.seed
+            && self.particles == other.particles
+    }
+}
+
+impl Display for Emitter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "emitter at {} rate {} per frame ({} particles)", self.position, self.rate, self.particles.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::video::Frame;
+    use super::*;
+
+    #[test]
+    fn test_lifetime() {
+        let mut e = Emitter::new(Position::new(5, 5), 2);
+        e.lifetime = 3;
+        e.gravity = 1.0;
+        e.spread = 0.0;
+        for _ in 0..5 {
+            e.step();
+        }
+        // particles emitted in the last 3 steps are alive
+        assert_eq!(e.particle_count(), 6);
+        // the oldest has fallen by 1 + 2
+        assert_eq!(e.particles[0].position, (5.0, 8.0));
+        e.emitting = false;
+        e.step();
+        assert_eq!(e.particle_count(), 4);
+    }
+
+    #[test]
+    fn test_seed() {
+        let run = |seed| {
+            let mut e = Emitter::new(Position::new(5, 5), 3);
+            e.seed(seed);
+            e.step();
+            e.step();
+            e.particles
+        };
+        assert_eq!(run(7), run(7));
+        assert_ne!(run(7), run(8));
+    }
+
+    #[test]
+    fn test_draw_at_edge() {
+        let mut e = Emitter::new(Position::new(5, 5), 0);
+        e.sizes = (2.0, 2.0);
+        e.particles.push(Particle { position: (5.0, 10.5), velocity: (0.0, 0.0), age: 0 });
+        e.particles.push(Particle { position: (5.0, 50.0), velocity: (0.0, 0.0), age: 0 });
+        let mut f = Frame::from_pixel(10, 10, Color::from([0, 0, 0]));
+        e.draw(&mut f, false);
+        // the particle below the frame shows at the bottom only, it does not wrap to the top
+        assert_eq!(f.get_pixel(5, 9).0, [255, 255, 255]);
+        assert!((0..2).all(|y| (0..10).all(|x| f.get_pixel(x, y).0 == [0, 0, 0])));
+    }
+}
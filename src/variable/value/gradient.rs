@@ -0,0 +1,73 @@
+use std::fmt::Display;
+
+use super::{Color, Position, color};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gradient {
+    /// Colors change along the line from `start` to `end`, they stay constant beyond its ends.
+    Linear { from: Color, start: Position, to: Color, end: Position },
+    /// Colors change with the distance from `center`, they stay constant beyond `radius`.
+    Radial { from: Color, center: Position, to: Color, radius: f64 },
+}
+
+impl Gradient {
+    pub fn default() -> Self {
+        let black = Color::from([0,0,0]);
+        Self::Linear { from: black, start: Position::default(), to: black, end: Position::default() }
+    }
+
+    /// Color of the gradient at the given pixel.
+    pub fn color_at(&self, x: f64, y: f64) -> Color {
+        match self {
+            Self::Linear { from, start, to, end } => {
+                let (sx, sy) = start.exact();
+                let (ex, ey) = end.exact();
+                let (dx, dy) = (ex - sx, ey - sy);
+                let len2 = dx * dx + dy * dy;
+                if len2 == 0.0 {
+                    return *from;
+                }
+                let t = ((x - sx) * dx + (y - sy) * dy) / len2;
+                color::mix(*from, *to, t)
+            }
+            Self::Radial { from, center, to, radius } => {
+                let (cx, cy) = center.exact();
+                if *radius <= 0.0 {
+                    return *to;
+                }
+                let t = ((x - cx).powi(2) + (y - cy).powi(2)).sqrt() / radius;
+                color::mix(*from, *to, t)
+            }
+        }
+    }
+}
+
+impl Display for Gradient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let c = |c: &Color| format!("{{{},{},{}}}", c.0[0], c.0[1], c.0[2]);
+        match self {
+            Self::Linear { from, start, to, end } => write!(f, "gradient from {} at {} to {} at {}", c(from), start, c(to), end),
+            Self::Radial { from, center, to, radius } => write!(f, "radial gradient from {} at {} to {} at radius {}", c(from), center, c(to), radius),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_at() {
+        let red = Color::from([255,0,0]);
+        let blue = Color::from([0,0,255]);
+        let g = Gradient::Linear { from: red, start: Position::new(0, 0), to: blue, end: Position::new(0, 100) };
+        assert_eq!(g.color_at(50.0, 0.0), red);
+        assert_eq!(g.color_at(50.0, -10.0), red);
+        assert_eq!(g.color_at(0.0, 50.0), Color::from([128,0,128]));
+        assert_eq!(g.color_at(0.0, 200.0), blue);
+        let g = Gradient::Radial { from: red, center: Position::new(10, 10), to: blue, radius: 10.0 };
+        assert_eq!(g.color_at(10.0, 10.0), red);
+        assert_eq!(g.color_at(10.0, 15.0), Color::from([128,0,128]));
+        assert_eq!(g.color_at(30.0, 10.0), blue);
+    }
+}
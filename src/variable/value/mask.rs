@@ -0,0 +1,159 @@
+use std::fmt::Display;
+
+use image::{GrayImage, Luma, imageops};
+
+use super::color;
+use crate::video::{Image, raster::Shape};
+
+/// Per-pixel opacity used to limit drawing to a part of the frame.
+/// Outside of its image the mask is fully transparent (or fully opaque when inverted).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mask {
+    /// Position of the top left corner of `alpha` in the frame.
+    origin: (i32, i32),
+    alpha: GrayImage,
+    inverted: bool,
+    /// Sides (left, top, right, bottom) of `alpha` cut off at the edges of the frame,
+    /// the mask continues past them the same as at the edge.
+    cut: [bool; 4],
+}
+
+impl Mask {
+    pub fn default() -> Self {
+        Self { origin: (0, 0), alpha: GrayImage::new(0, 0), inverted: false, cut: [false; 4] }
+    }
+
+    /// Mask covering the pixels of the shape, with `antialias` partially covered pixels are partially opaque.
+    /// The mask only keeps the part of the shape inside of a frame of size `frame` (when it is known).
+    pub fn from_shape(shape: &Shape, antialias: bool, frame: Option<(u32, u32)>) -> Self {
+        let whole = shape.bounds();
+        let (l, t, r, b) = frame.map_or(whole, |size| shape.bounds_within(size));
+        let mut alpha = GrayImage::new((r - l).max(0) as u32, (b - t).max(0) as u32);
+        for (x, y, coverage) in shape.covered_pixels((l, t, r, b), antialias) {
+            alpha.put_pixel((x - l) as u32, (y - t) as u32, Luma([(coverage * 255.0).round() as u8]));
+        }
+        let cut = [l > whole.0, t > whole.1, r < whole.2, b < whole.3];
+        Self { origin: (l, t), alpha, inverted: false, cut }
+    }
+
+    /// Bright parts of the image are opaque, transparent parts of the image stay transparent.
+    pub fn from_luminance(img: &Image, origin: (i32, i32)) -> Self {
+        let alpha = GrayImage::from_fn(img.width(), img.height(), |x, y| {
+            let p = img.get_pixel(x, y);
+            let l = color::luminance([p[0], p[1], p[2]].into());
+            Luma([(l * p[3] as f64 / 255.0).round() as u8])
+        });
+        Self { origin, alpha, inverted: false, cut: [false; 4] }
+    }
+
+    /// Opacity of the mask is the opacity of the image.
+    pub fn from_alpha(img: &Image, origin: (i32, i32)) -> Self {
+        let alpha = GrayImage::from_fn(img.width(), img.height(), |x, y| Luma([img.get_pixel(x, y)[3]]));
+        Self { origin, alpha, inverted: false, cut: [false; 4] }
+    }
+
+    /// Opaque parts become transparent and the other way around.
+    pub fn invert(&mut self) {
+        self.alpha.pixels_mut().for_each(|p| p.0[0] = 255 - p.0[0]);
+        self.inverted = !self.inverted;
+    }
+
+    /// Soften the edges of the mask, they fade out over roughly `radius` pixels.
+    pub fn feather(&mut self, radius: u32) {
+        if radius == 0 {
+            return;
+        }
+        // make room for the edges to spread out, sides cut off at the frame edges do not fade
+        let outside = Luma([if self.inverted { 255 } else { 0 }]);
+        let (w, h) = (self.alpha.width() as i64, self.alpha.height() as i64);
+        let [cut_l, cut_t, cut_r, cut_b] = self.cut;
+        let within = |v: i64, size: i64, cut_low: bool, cut_high: bool| match v {
+            _ if size == 0 => None,
+            v if v < 0 => cut_low.then_some(0),
+            v if v >= size => cut_high.then_some(size - 1),
+            v => Some(v),
+        };
+        let padded = GrayImage::from_fn(w as u32 + 2 * radius, h as u32 + 2 * radius, |x, y| {
+            let x = within(x as i64 - radius as i64, w, cut_l, cut_r);
+            let y = within(y as i64 - radius as i64, h, cut_t, cut_b);
+            match (x, y) {
+                (Some(x), Some(y)) => *self.alpha.get_pixel(x as u32, y as u32),
+                _ => outside,
+            }
+        });
+        self.alpha = imageops::blur(&padded, radius as f32 / 2.0);
+        self.origin = (self.origin.0 - radius as i32, self.origin.1 - radius as i32);
+    }
+
+    /// Opacity of the mask at a pixel of the frame (0.0 - 1.0).
+    pub fn coverage(&self, x: i32, y: i32) -> f64 {
+        let (x, y) = (x - self.origin.0, y - self.origin.1);
+        if x < 0 || y < 0 || x >= self.alpha.width() as i32 || y >= self.alpha.height() as i32 {
+            return if self.inverted { 1.0 } else { 0.0 };
+        }
+        self.alpha.get_pixel(x as u32, y as u32)[0] as f64 / 255.0
+    }
+
+    /// Region `(left, top, right, bottom)` of an image of the given size the mask can affect.
+    pub fn region(&self, width: u32, height: u32) -> (u32, u32, u32, u32) {
+        if self.inverted {
+            return (0, 0, width, height);
+        }
+        let (l, t) = self.origin;
+        let (r, b) = (l + self.alpha.width() as i32, t + self.alpha.height() as i32);
+        let clip = |v: i32, max: u32| v.clamp(0, max as i32) as u32;
+        (clip(l, width), clip(t, height), clip(r, width), clip(b, height))
+    }
+}
+
+impl Display for Mask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inverted = if self.inverted { "inverted " } else { "" };
+        write!(f, "{inverted}mask {}x{} at ({},{})", self.alpha.width(), self.alpha.height(), self.origin.0, self.origin.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask() {
+        let square = Shape::Polygon(vec![(2.0, 2.0), (4.0, 2.0), (4.0, 4.0), (2.0, 4.0)]);
+        let mut m = Mask::from_shape(&square, true, Some((10, 10)));
+        assert_eq!(m.coverage(2, 3), 1.0);
+        assert_eq!(m.coverage(4, 3), 0.0);
+        assert_eq!(m.region(3, 10), (1, 1, 3, 5));
+        m.invert();
+        assert_eq!(m.coverage(2, 3), 0.0);
+        assert_eq!(m.coverage(-10, 3), 1.0);
+        assert_eq!(m.region(3, 10), (0, 0, 3, 10));
+
+        let img = Image::from_fn(2, 1, |x, _| image::Rgba([255, 255, 255, if x == 0 { 255 } else { 0 }]));
+        let m = Mask::from_luminance(&img, (5, 5));
+        assert_eq!((m.coverage(5, 5), m.coverage(6, 5)), (1.0, 0.0));
+    }
+
+    #[test]
+    fn test_feather() {
+        let mut m = Mask::from_shape(&Shape::Polygon(vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]), true, None);
+        m.feather(2);
+        assert!(m.coverage(5, 5) > 0.9);
+        assert!(m.coverage(-1, 5) > 0.0 && m.coverage(-1, 5) < 0.5);
+        assert!(m.coverage(0, 5) < 1.0);
+    }
+
+    #[test]
+    fn test_cut_at_frame() {
+        let huge = Shape::Ellipse { center: (5.0, 5.0), radii: (1e9, 1e9), outline: None };
+        let mut m = Mask::from_shape(&huge, true, Some((10, 10)));
+        assert_eq!(m.alpha.dimensions(), (10, 10));
+        // the edges of the frame are not edges of the shape, they stay opaque
+        m.feather(3);
+        assert_eq!((m.coverage(0, 0), m.coverage(9, 5)), (1.0, 1.0));
+        // the shape misses the frame
+        let mut m = Mask::from_shape(&Shape::Ellipse { center: (-20.0, 5.0), radii: (3.0, 3.0), outline: None }, true, Some((10, 10)));
+        m.feather(3);
+        assert_eq!(m.coverage(0, 5), 0.0);
+    }
+}
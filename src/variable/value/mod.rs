@@ -1,16 +1,29 @@
 mod structure;
 mod direction;
 mod effect;
+mod gradient;
+mod mask;
+mod transition;
+mod edges;
+mod sprite;
+mod emitter;
 mod rectangle;
 mod position;
 mod column;
 mod value;
+pub mod color;
 
 pub type Color = image::Rgb<u8>;
 
 pub use structure::Structure;
 pub use direction::Direction;
 pub use effect::Effect;
+pub use gradient::Gradient;
+pub use mask::Mask;
+pub use transition::Transition;
+pub use edges::Edges;
+pub use sprite::{Sprite, Look};
+pub use emitter::Emitter;
 pub use rectangle::Rectangle;
 pub use position::Position;
 pub use value::VariableValue;
@@ -1,23 +1,100 @@
 use std::fmt::Display;
 
-#[derive(Clone, Debug, PartialEq, Eq, Copy)]
+/// Pixel coordinates, fractional moves also keep the exact coordinates within the pixel.
+/// Positions are compared by their pixels.
+#[derive(Clone, Debug, Copy)]
 pub struct Position {
     pub x: i32,
     pub y: i32,
+    /// Exact coordinates, only used while they lie in the pixel `(x, y)`, so that writing
+    /// the pixel coordinates directly cannot leave a stale sub-pixel part behind.
+    exact: Option<(f64, f64)>,
 }
 
 impl Position {
     pub fn new(x: i32, y: i32) -> Self {
-        Self { x, y }
+        Self { x, y, exact: None }
     }
 
     pub fn default() -> Self {
-        Self { x: 0, y: 0 }
+        Self::new(0, 0)
+    }
+
+    /// Create a position from exact coordinates, the pixel is the one containing them.
+    pub fn from_exact(x: f64, y: f64) -> Self {
+        Self { x: x.floor() as i32, y: y.floor() as i32, exact: Some((x, y)) }
+    }
+
+    /// Set the pixel coordinates, the sub-pixel part is dropped.
+    pub fn set(&mut self, x: i32, y: i32) {
+        *self = Self::new(x, y);
+    }
+
+    /// Get the exact coordinates, including the sub-pixel part.
+    pub fn exact(&self) -> (f64, f64) {
+        match self.exact {
+            Some((x, y)) if x.floor() as i32 == self.x && y.floor() as i32 == self.y => (x, y),
+            _ => (self.x as f64, self.y as f64),
+        }
+    }
+
+    /// Move the position by a (possibly fractional) offset.
+    pub fn translate(&mut self, dx: f64, dy: f64) {
+        let (x, y) = self.exact();
+        *self = Self::from_exact(x + dx, y + dy);
+    }
+
+    pub fn has_fract(&self) -> bool {
+        self.exact() != (self.x as f64, self.y as f64)
     }
 }
 
+impl PartialEq for Position {
+    fn eq(&self, other: &Self) -> bool {
+        (self.x, self.y) == (other.x, other.y)
+    }
+}
+
+impl Eq for Position {}
+
 impl Display for Position {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({},{})", self.x, self.y)
+        if self.has_fract() {
+            let (x, y) = self.exact();
+            write!(f, "({x:.2},{y:.2})")
+        } else {
+            write!(f, "({},{})", self.x, self.y)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate() {
+        let mut p = Position::new(1, 1);
+        for _ in 0..10 {
+            p.translate(0.25, -0.25);
+        }
+        assert_eq!((p.x, p.y), (3, -2));
+        assert!((p.exact().0 - 3.5).abs() < 1e-9);
+        assert!((p.exact().1 + 1.5).abs() < 1e-9);
+        // positions on the same pixel are equal
+        assert_eq!(p, Position::new(3, -2));
+        p.translate(0.5, 0.5);
+        assert_eq!(p, Position::new(4, -1));
+        assert!(!p.has_fract());
+    }
+
+    #[test]
+    fn test_pixel_writes() {
+        let mut p = Position::from_exact(1.75, 2.5);
+        p.x = 5;
+        assert_eq!(p.exact(), (5.0, 2.0));
+        let mut p = Position::from_exact(1.75, 2.5);
+        p.set(1, 2);
+        assert!(!p.has_fract());
     }
 }
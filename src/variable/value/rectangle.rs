@@ -16,6 +16,19 @@ impl Rectangle {
     pub fn default() -> Self {
         Self::new(Position::default(), Position::default())
     }
+
+    /// Check whether the position lies in the rectangle.
+    /// The top left corner is inclusive, the bottom right one is exclusive (same as when drawing).
+    pub fn contains(&self, pos: &Position) -> bool {
+        self.top_left.x <= pos.x && pos.x < self.bot_right.x
+            && self.top_left.y <= pos.y && pos.y < self.bot_right.y
+    }
+
+    /// Check whether the two rectangles share at least one pixel.
+    pub fn overlaps(&self, other: &Rectangle) -> bool {
+        self.top_left.x < other.bot_right.x && other.top_left.x < self.bot_right.x
+            && self.top_left.y < other.bot_right.y && other.top_left.y < self.bot_right.y
+    }
 }
 
 impl Display for Rectangle {
@@ -23,3 +36,28 @@ impl Display for Rectangle {
         write!(f, "rectangle from {} to {}", self.top_left, self.bot_right)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains() {
+        let r = Rectangle::new(Position::new(0, 0), Position::new(10, 5));
+        assert!(r.contains(&Position::new(0, 0)));
+        assert!(r.contains(&Position::new(9, 4)));
+        assert!(!r.contains(&Position::new(10, 4)));
+        assert!(!r.contains(&Position::new(-1, 2)));
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let r1 = Rectangle::new(Position::new(0, 0), Position::new(10, 10));
+        let r2 = Rectangle::new(Position::new(5, 5), Position::new(15, 15));
+        let r3 = Rectangle::new(Position::new(10, 0), Position::new(20, 10));
+        assert!(r1.overlaps(&r2));
+        assert!(r2.overlaps(&r1));
+        assert!(!r1.overlaps(&r3)); // only touching edges
+        assert!(r2.overlaps(&r3));
+    }
+}
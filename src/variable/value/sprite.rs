@@ -0,0 +1,160 @@
+use std::fmt::Display;
+
+use super::{Color, Edges, Position, Rectangle};
+use crate::video::{Canvas, Image, Transformable, raster::Shape};
+
+/// What a sprite looks like before it is scaled and rotated.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Look {
+    Image(Image),
+    /// Rectangle of the given width and height.
+    Rectangle(Color, u32, u32),
+    Circle(Color, u32),
+}
+
+/// Graphical object the runtime moves by its velocity and draws on every frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sprite {
+    pub look: Look,
+    /// Position of the center of the sprite.
+    pub position: Position,
+    /// Change of position per frame.
+    pub velocity: (f64, f64),
+    /// Clockwise rotation in degrees.
+    pub rotation: i32,
+    pub scale: f64,
+    /// Opacity of the whole sprite (0.0 - 1.0).
+    pub opacity: f64,
+    pub visible: bool,
+    /// What happens when the sprite reaches an edge of the frame.
+    pub edges: Edges,
+    /// Layer the sprite is drawn on, `None` is the frame itself.
+    pub layer: Option<String>,
+}
+
+impl Sprite {
+    pub fn new(look: Look, position: Position) -> Self {
+        Self { look, position, velocity: (0.0, 0.0), rotation: 0, scale: 1.0, opacity: 1.0, visible: true, edges: Edges::Ignore, layer: None }
+    }
+
+    pub fn default() -> Self {
+        Self::new(Look::Rectangle(Color::from([0, 0, 0]), 0, 0), Position::default())
+    }
+
+    /// Width and height before rotation.
+    fn size(&self) -> (f64, f64) {
+        let (w, h) = match &self.look {
+            Look::Image(img) => img.dimensions(),
+            Look::Rectangle(_, w, h) => (*w, *h),
+            Look::Circle(_, r) => (2 * r, 2 * r),
+        };
+        (w as f64 * self.scale, h as f64 * self.scale)
+    }
+
+    /// Exact bounds `(left, top, right, bottom)` of the sprite, rotation is not taken into account.
+    fn bounds(&self) -> (f64, f64, f64, f64) {
+        let (x, y) = self.position.exact();
+        let (w, h) = self.size();
+        (x - w / 2.0, y - h / 2.0, x + w / 2.0, y + h / 2.0)
+    }
+
+    /// Rectangle covered by the sprite (before rotation).
+    pub fn rectangle(&self) -> Rectangle {
+        let (l, t, r, b) = self.bounds();
+        Rectangle::new(Position::new(l.floor() as i32, t.floor() as i32), Position::new(r.ceil() as i32, b.ceil() as i32))
+    }
+
+    /// Move the sprite by its velocity, then handle the edges of a `width` x `height` frame.
+    pub fn step(&mut self, width: u32, height: u32) {
+        self.position.translate(self.velocity.0, self.velocity.1);
+        let (l, t, r, b) = self.bounds();
+        let (mut x, mut y) = (l, t);
+        self.edges.constrain(&mut x, r - l, &mut self.velocity.0, width as f64);
+        self.edges.constrain(&mut y, b - t, &mut self.velocity.1, height as f64);
+        self.position.translate(x - l, y - t);
+    }
+
+    /// Scaled and rotated picture of the sprite.
+    fn picture(&self) -> Image {
+        let (w, h) = self.size();
+        let (w, h) = (w.round() as u32, h.round() as u32);
+        let mut img = match &self.look {
+            Look::Image(img) => {
+                let mut img = img.clone();
+                img.scale_to(w, h);
+                img
+            }
+            Look::Rectangle(c, _, _) => Image::from_pixel(w, h, image::Rgba([c[0], c[1], c[2], 255])),
+            Look::Circle(c, _) => {
+                let mut img = Image::new(w, h);
+                let circle = Shape::Ellipse { center: (w as f64 / 2.0, h as f64 / 2.0), radii: (w as f64 / 2.0, h as f64 / 2.0), outline: None };
+                img.fill_shape(&circle, |_, _| *c, true);
+                img
+            }
+        };
+        img.rotate_by(self.rotation);
+        img
+    }
+
+    /// Draw the sprite centered at its position.
+    pub fn draw<C: Canvas>(&self, canvas: &mut C) {
+        let (w, h) = self.size();
+        if !self.visible || self.opacity <= 0.0 || w.round() < 1.0 || h.round() < 1.0 {
+            return;
+        }
+        let img = self.picture();
+        let (x, y) = self.position.exact();
+        let left = (x - img.width() as f64 / 2.0).round() as i64;
+        let top = (y - img.height() as f64 / 2.0).round() as i64;
+        canvas.draw_image_with_opacity(&img, left, top, self.opacity);
+    }
+}
+
+impl Display for Sprite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let look = match &self.look {
+            Look::Image(img) => format!("image {}x{}", img.width(), img.height()),
+            Look::Rectangle(_, w, h) => format!("rectangle {w}x{h}"),
+            Look::Circle(_, r) => format!("circle of radius {r}"),
+        };
+        write!(f, "sprite of {look} at {}", self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounce() {
+        let mut s = Sprite::new(Look::Rectangle(Color::from([255, 0, 0]), 4, 2), Position::new(7, 5));
+        s.velocity = (2.0, -1.0);
+        s.step(10, 10);
+        assert_eq!(s.position, Position::new(9, 4));
+        s.edges = Edges::Bounce;
+        s.step(10, 10);
+        // right edge would be at 13, it is reflected to 7
+        assert_eq!(s.position, Position::new(5, 3));
+        assert_eq!(s.velocity, (-2.0, -1.0));
+        assert_eq!(s.rectangle(), Rectangle::new(Position::new(3, 2), Position::new(7, 4)));
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut s = Sprite::new(Look::Rectangle(Color::from([255, 0, 0]), 2, 2), Position::new(2, 2));
+        let mut f = crate::video::Frame::new(4, 4);
+        s.draw(&mut f);
+        assert_eq!(*f.get_pixel(1, 1), Color::from([255, 0, 0]));
+        assert_eq!(*f.get_pixel(3, 3), Color::from([0, 0, 0]));
+        s.visible = false;
+        s.scale = 2.0;
+        let mut f = crate::video::Frame::new(4, 4);
+        s.draw(&mut f);
+        assert_eq!(*f.get_pixel(1, 1), Color::from([0, 0, 0]));
+        // scaled down to nothing
+        s.visible = true;
+        s.scale = 0.0;
+        s.draw(&mut f);
+        assert_eq!(*f.get_pixel(1, 1), Color::from([0, 0, 0]));
+    }
+}
@@ -3,7 +3,7 @@ use std::fmt::Display;
 use crate::variable::{VariableValue, Scope, Stack};
 
 /// Instance of a user defined structure
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Structure {
     pub id: usize,
     members: Scope,
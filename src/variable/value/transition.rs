@@ -0,0 +1,36 @@
+use std::fmt::Display;
+
+use super::{Color, Direction};
+
+/// The way one picture is replaced by another over several frames.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transition {
+    /// The pictures are blended.
+    Crossfade,
+    /// The first picture fades to the color, then the color fades to the second picture.
+    Dip(Color),
+    /// The second picture is uncovered by an edge moving in the direction.
+    Wipe(Direction),
+    /// The second picture moves in in the direction, pushing the first one out.
+    Slide(Direction),
+    /// The second picture grows from the center over the first one.
+    Zoom,
+}
+
+impl Transition {
+    pub fn default() -> Self {
+        Self::Crossfade
+    }
+}
+
+impl Display for Transition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Crossfade => write!(f, "crossfade"),
+            Self::Dip(c) => write!(f, "dip to {{{},{},{}}}", c.0[0], c.0[1], c.0[2]),
+            Self::Wipe(d) => write!(f, "wipe {d}"),
+            Self::Slide(d) => write!(f, "slide {d}"),
+            Self::Zoom => write!(f, "zoom in"),
+        }
+    }
+}
@@ -1,24 +1,31 @@
 use std::fmt::Display;
 
-use crate::{variable::{Row, Variable, VariableType, value::column::Column}, video::Frame};
+use crate::{variable::{Row, Variable, VariableType, value::column::Column}, video::Image};
 
-use super::{Color,Effect,Direction,Structure,Rectangle,Position};
+use super::{Color,Effect,Gradient,Mask,Transition,Sprite,Emitter,Direction,Structure,Rectangle,Position};
 
 /// Values of variables
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum VariableValue {
     Any(usize),
     Int(i32),
+    Float(f64),
+    Bool(bool),
     Pos(Position),
     String(String),
     Color(Color),
     Effect(Effect),
+    Gradient(Gradient),
+    Mask(Mask),
+    Transition(Transition),
+    Sprite(Sprite),
+    Emitter(Emitter),
     Direction(Direction),
     Rectangle(Rectangle),
     Structure(Structure),
     Column(Column),
     Row(Row),
-    Image(Frame),
+    Image(Image),
     // This exists only as a default value of respective VariableType
     // It should not be directly used, outside of method parsing
     SelfReference,
@@ -39,10 +46,17 @@ impl VariableValue {
     pub fn get_type(&self) -> VariableType {
         match self {
             Self::Int(_) => VariableType::Int,
+            Self::Float(_) => VariableType::Float,
+            Self::Bool(_) => VariableType::Bool,
             Self::Pos(_) => VariableType::Pos,
             Self::Color(_) => VariableType::Color,
             Self::String(_) => VariableType::String,
             Self::Effect(_) => VariableType::Effect,
+            Self::Gradient(_) => VariableType::Gradient,
+            Self::Mask(_) => VariableType::Mask,
+            Self::Transition(_) => VariableType::Transition,
+            Self::Sprite(_) => VariableType::Sprite,
+            Self::Emitter(_) => VariableType::Emitter,
             Self::Direction(_) => VariableType::Direction,
             Self::Column(_) => VariableType::Column,
             Self::Row(_) => VariableType::Row,
@@ -80,6 +94,16 @@ impl VariableValue {
         *i
     }
 
+    pub fn into_float(&self) -> f64 {
+        let Self::Float(f) = self else { panic!(); };
+        *f
+    }
+
+    pub fn into_bool(&self) -> bool {
+        let Self::Bool(b) = self else { panic!(); };
+        *b
+    }
+
     pub fn into_pos(&self) -> Position {
         let Self::Pos(pos) = self else { panic!(); };
         *pos
@@ -115,12 +139,47 @@ impl VariableValue {
         *e
     }
 
-    pub fn into_image(&self) -> &Frame {
+    pub fn into_gradient(&self) -> Gradient {
+        let Self::Gradient(g) = self else { panic!(); };
+        *g
+    }
+
+    pub fn into_mask(&self) -> &Mask {
+        let Self::Mask(m) = self else { panic!(); };
+        m
+    }
+
+    pub fn into_mask_mut(&mut self) -> &mut Mask {
+        let Self::Mask(m) = self else { panic!(); };
+        m
+    }
+
+    pub fn into_transition(&self) -> Transition {
+        let Self::Transition(t) = self else { panic!(); };
+        *t
+    }
+
+    pub fn into_sprite(&self) -> &Sprite {
+        let Self::Sprite(s) = self else { panic!(); };
+        s
+    }
+
+    pub fn into_sprite_mut(&mut self) -> &mut Sprite {
+        let Self::Sprite(s) = self else { panic!(); };
+        s
+    }
+
+    pub fn into_emitter_mut(&mut self) -> &mut Emitter {
+        let Self::Emitter(e) = self else { panic!(); };
+        e
+    }
+
+    pub fn into_image(&self) -> &Image {
         let Self::Image(i) = self else { panic!(); };
         i
     }
 
-    pub fn into_image_mut(&mut self) -> &mut Frame {
+    pub fn into_image_mut(&mut self) -> &mut Image {
         let Self::Image(i) = self else { panic!(); };
         i
     }
@@ -141,6 +200,8 @@ impl Display for VariableValue {
         match self {
             Self::Any(b) => write!(f, "Any({b})"),
             Self::Int(i) => write!(f, "{i}"),
+            Self::Float(x) => write!(f, "{x}"),
+            Self::Bool(b) => write!(f, "{b}"),
             Self::Pos(pos) => write!(f, "{pos}"),
             Self::Rectangle(r) => write!(f, "{r}"),
             Self::String(s) => write!(f, "\"{s}\""),
@@ -148,6 +209,11 @@ impl Display for VariableValue {
             Self::Row(r) => write!(f, "{r}"),
             Self::Color(c) => write!(f, "{{{},{},{}}}",c.0[0],c.0[1],c.0[2]),
             Self::Effect(e) => write!(f, "{e}"),
+            Self::Gradient(g) => write!(f, "{g}"),
+            Self::Mask(m) => write!(f, "{m}"),
+            Self::Transition(t) => write!(f, "{t}"),
+            Self::Sprite(s) => write!(f, "{s}"),
+            Self::Emitter(e) => write!(f, "{e}"),
             Self::Direction(d) => write!(f, "{d}"),
             Self::Structure(s) => write!(f, "{s}"),
             Self::Image(i) => write!(f, "image {}x{}", i.width(), i.height()),
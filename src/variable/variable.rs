@@ -5,7 +5,7 @@ use super::{ VariableValue,VariableType,Stack };
 /// Representation of variables
 /// Static -> literals, such as 1, "hey", red
 /// Named  -> variables on the stack
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Variable {
     Static(VariableValue),
     Named(String, VariableType),
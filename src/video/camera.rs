@@ -0,0 +1,126 @@
+use super::Frame;
+use crate::variable::Color;
+
+/// Transformation of the whole output frame ("camera movement").
+/// Transformations are queued while the frame is processed and applied right before it is written,
+/// the output keeps its dimensions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CameraTransform {
+    /// Show only the area `(left, top, right, bottom)`, it is enlarged around its center
+    /// to the aspect ratio of the frame, so the picture is never distorted.
+    Zoom(f64, f64, f64, f64),
+    /// Show exactly the area `(left, top, right, bottom)`, stretched over the whole frame.
+    Crop(f64, f64, f64, f64),
+    /// Move the picture by an offset, uncovered parts are black.
+    Pan(f64, f64),
+    /// Rotate the picture clockwise around the center of the frame.
+    Rotate(f64),
+}
+
+impl CameraTransform {
+    pub fn apply(&self, frame: &mut Frame) {
+        let (w, h) = (frame.width() as f64, frame.height() as f64);
+        if w == 0.0 || h == 0.0 {
+            return;
+        }
+        match *self {
+            CameraTransform::Zoom(l, t, r, b) => {
+                let (cx, cy) = ((l + r) / 2.0, (t + b) / 2.0);
+                let (mut rw, mut rh) = ((r - l).abs(), (b - t).abs());
+                if rw / rh.max(f64::EPSILON) < w / h {
+                    rw = rh * w / h;
+                } else {
+                    rh = rw * h / w;
+                }
+                CameraTransform::Crop(cx - rw / 2.0, cy - rh / 2.0, cx + rw / 2.0, cy + rh / 2.0).apply(frame);
+            }
+            CameraTransform::Crop(l, t, r, b) => {
+                let (sx, sy) = ((r - l) / w, (b - t) / h);
+                resample(frame, |x, y| (l + x * sx, t + y * sy));
+            }
+            CameraTransform::Pan(dx, dy) => {
+                resample(frame, |x, y| (x - dx, y - dy));
+            }
+            CameraTransform::Rotate(degrees) => {
+                let (sin, cos) = degrees.to_radians().sin_cos();
+                let (cx, cy) = (w / 2.0, h / 2.0);
+                resample(frame, |x, y| {
+                    let (dx, dy) = (x - cx, y - cy);
+                    (dx * cos + dy * sin + cx, -dx * sin + dy * cos + cy)
+                });
+            }
+        }
+    }
+}
+
+/// Rebuild the frame, each output pixel is sampled (bilinearly) from the point returned by `source`.
+/// Both the output and source points are continuous coordinates, pixel centers are at `x + 0.5`.
+fn resample<F: Fn(f64, f64) -> (f64, f64)>(frame: &mut Frame, source: F) {
+    let (w, h) = (frame.width() as i64, frame.height() as i64);
+    let black = Color::from([0, 0, 0]);
+    let get = |x: i64, y: i64| -> [f64; 3] {
+        let (x, y) = (x.clamp(0, w - 1), y.clamp(0, h - 1));
+        frame.get_pixel(x as u32, y as u32).0.map(|c| c as f64)
+    };
+    let out = Frame::from_fn(w as u32, h as u32, |x, y| {
+        let (sx, sy) = source(x as f64 + 0.5, y as f64 + 0.5);
+        let (sx, sy) = (sx - 0.5, sy - 0.5);
+        // points outside of the source frame are black
+        if sx < -0.5 || sy < -0.5 || sx > w as f64 - 0.5 || sy > h as f64 - 0.5 {
+            return black;
+        }
+        let (x0, y0) = (sx.floor(), sy.floor());
+        let (fx, fy) = (sx - x0, sy - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+        let (p00, p10, p01, p11) = (get(x0, y0), get(x0 + 1, y0), get(x0, y0 + 1), get(x0 + 1, y0 + 1));
+        let mut c = [0u8; 3];
+        for i in 0..3 {
+            let top = p00[i] * (1.0 - fx) + p10[i] * fx;
+            let bottom = p01[i] * (1.0 - fx) + p11[i] * fx;
+            c[i] = (top * (1.0 - fy) + bottom * fy).round() as u8;
+        }
+        Color::from(c)
+    });
+    *frame = out;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame() -> Frame {
+        // left half white, right half black
+        Frame::from_fn(8, 4, |x, _| if x < 4 { Color::from([255, 255, 255]) } else { Color::from([0, 0, 0]) })
+    }
+
+    #[test]
+    fn test_pan() {
+        let mut f = frame();
+        CameraTransform::Pan(2.0, 0.0).apply(&mut f);
+        assert_eq!(f.dimensions(), (8, 4));
+        assert_eq!(f.get_pixel(0, 0).0, [0, 0, 0]);
+        assert_eq!(f.get_pixel(2, 0).0, [255, 255, 255]);
+        assert_eq!(f.get_pixel(5, 0).0, [255, 255, 255]);
+        assert_eq!(f.get_pixel(6, 0).0, [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_crop_and_zoom() {
+        let mut f = frame();
+        CameraTransform::Crop(0.0, 0.0, 3.0, 4.0).apply(&mut f);
+        assert_eq!(f.dimensions(), (8, 4));
+        assert!(f.pixels().all(|p| p.0 == [255, 255, 255]));
+        let mut f = frame();
+        // a 1x1 square is widened to 2x1, which still lies in the white half
+        CameraTransform::Zoom(1.0, 1.0, 2.0, 2.0).apply(&mut f);
+        assert!(f.pixels().all(|p| p.0 == [255, 255, 255]));
+    }
+
+    #[test]
+    fn test_rotate() {
+        let mut f = frame();
+        CameraTransform::Rotate(180.0).apply(&mut f);
+        assert_eq!(f.get_pixel(0, 0).0, [0, 0, 0]);
+        assert_eq!(f.get_pixel(7, 3).0, [255, 255, 255]);
+    }
+}
@@ -0,0 +1,114 @@
+use image::{Pixel, Rgba};
+
+use super::Image;
+use crate::variable::Color;
+
+/// Chroma keying ("green screen"), the keyed out parts of an image become transparent.
+pub trait Keyable {
+    /// Make pixels of a color similar to `key` transparent. Similarity is the distance of the colors
+    /// in the chroma (CbCr) plane, so shadows on the screen are keyed out too. Pixels closer than
+    /// `tolerance` are fully transparent, the alpha then rises linearly up to 1.5 * `tolerance`.
+    fn key_out(&mut self, key: Color, tolerance: i32);
+    /// Remove the tint the screen reflects onto the foreground: the dominant channel of `key`
+    /// is limited to the brighter of the other two channels.
+    fn suppress_spill(&mut self, key: Color);
+    /// Soften the edges of opaque parts, alpha is blurred over `radius` pixels,
+    /// the opaque parts only shrink (transparent pixels stay transparent).
+    fn feather(&mut self, radius: i32);
+}
+
+/// Blue and red difference chroma components (Rec. 601).
+fn chroma(c: Color) -> (f64, f64) {
+    let [r, g, b] = c.0.map(|x| x as f64);
+    (-0.168736 * r - 0.331264 * g + 0.5 * b, 0.5 * r - 0.418688 * g - 0.081312 * b)
+}
+
+impl Keyable for Image {
+    fn key_out(&mut self, key: Color, tolerance: i32) {
+        let (kb, kr) = chroma(key);
+        let tolerance = tolerance.max(0) as f64;
+        let ramp = (tolerance / 2.0).max(1.0);
+        for p in self.pixels_mut() {
+            let (cb, cr) = chroma(p.to_rgb());
+            let distance = ((cb - kb).powi(2) + (cr - kr).powi(2)).sqrt();
+            let keep = ((distance - tolerance) / ramp).clamp(0.0, 1.0);
+            p[3] = (p[3] as f64 * keep).round() as u8;
+        }
+    }
+
+    fn suppress_spill(&mut self, key: Color) {
+        let dominant = (0..3).max_by_key(|i| key[*i]).unwrap();
+        let (o1, o2) = ((dominant + 1) % 3, (dominant + 2) % 3);
+        for p in self.pixels_mut() {
+            p[dominant] = p[dominant].min(p[o1].max(p[o2]));
+        }
+    }
+
+    fn feather(&mut self, radius: i32) {
+        if radius <= 0 {
+            return;
+        }
+        let (w, h) = self.dimensions();
+        let alpha: Vec<f64> = self.pixels().map(|p| p[3] as f64).collect();
+        let horizontal = box_blur(&alpha, w as usize, h as usize, radius as usize, 1, w as usize);
+        let blurred = box_blur(&horizontal, h as usize, w as usize, radius as usize, w as usize, 1);
+        for (p, a) in self.pixels_mut().zip(blurred) {
+            let Rgba([_, _, _, old]) = *p;
+            p[3] = old.min(a.round() as u8);
+        }
+    }
+}
+
+/// Average over `2 * radius + 1` values along lines of `len` values, `step` is the distance
+/// of neighbours in a line and `line_step` the distance of lines. Edges are extended.
+fn box_blur(values: &[f64], len: usize, lines: usize, radius: usize, step: usize, line_step: usize) -> Vec<f64> {
+    let mut out = vec![0.0; values.len()];
+    let count = (2 * radius + 1) as f64;
+    for line in 0..lines {
+        let at = |i: usize| values[line * line_step + i * step];
+        for i in 0..len {
+            let sum: f64 = (0..2 * radius + 1)
+                .map(|k| at((i + k).saturating_sub(radius).min(len - 1)))
+                .sum();
+            out[line * line_step + i * step] = sum / count;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GREEN: Color = image::Rgb([0, 255, 0]);
+
+    #[test]
+    fn test_key_out() {
+        let mut img = Image::from_fn(3, 1, |x, _| match x {
+            0 => Rgba([0, 255, 0, 255]),
+            1 => Rgba([10, 160, 10, 255]), // green in a shadow
+            _ => Rgba([200, 50, 50, 255]),
+        });
+        img.key_out(GREEN, 60);
+        assert_eq!(img.get_pixel(0, 0)[3], 0);
+        assert_eq!(img.get_pixel(1, 0)[3], 0);
+        assert_eq!(img.get_pixel(2, 0)[3], 255);
+    }
+
+    #[test]
+    fn test_suppress_spill() {
+        let mut img = Image::from_pixel(1, 1, Rgba([100, 180, 90, 255]));
+        img.suppress_spill(GREEN);
+        assert_eq!(*img.get_pixel(0, 0), Rgba([100, 100, 90, 255]));
+    }
+
+    #[test]
+    fn test_feather() {
+        // left half transparent
+        let mut img = Image::from_fn(6, 3, |x, _| Rgba([255, 255, 255, if x < 3 { 0 } else { 255 }]));
+        img.feather(1);
+        assert_eq!(img.get_pixel(2, 1)[3], 0);
+        assert_eq!(img.get_pixel(3, 1)[3], 170);
+        assert_eq!(img.get_pixel(5, 1)[3], 255);
+    }
+}
@@ -0,0 +1,177 @@
+use rayon::prelude::*;
+
+use super::Frame;
+use crate::variable::Color;
+
+/// Square convolution kernel with an odd size.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Kernel {
+    size: usize,
+    weights: Vec<f64>,
+    /// Added to every channel after the convolution (e.g. to center emboss around gray).
+    offset: f64,
+}
+
+impl Kernel {
+    /// Build a kernel from rows of weights, it is normalized by the sum of the weights
+    /// (unless they sum up to zero, as edge detection kernels do).
+    pub fn new(rows: Vec<Vec<f64>>) -> Result<Self, String> {
+        let size = rows.len();
+        if size.is_multiple_of(2) {
+            return Err(format!("kernel has to have an odd number of rows, got {size}"));
+        }
+        if let Some(row) = rows.iter().find(|r| r.len() != size) {
+            return Err(format!("kernel has to be square, got a row of length {} in a kernel with {size} rows", row.len()));
+        }
+        let mut weights: Vec<f64> = rows.into_iter().flatten().collect();
+        let sum: f64 = weights.iter().sum();
+        if sum != 0.0 {
+            weights.iter_mut().for_each(|w| *w /= sum);
+        }
+        Ok(Self { size, weights, offset: 0.0 })
+    }
+
+    pub fn with_offset(mut self, offset: f64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn gaussian_blur() -> Self {
+        Self::new(vec![
+            vec![1.0, 4.0, 6.0, 4.0, 1.0],
+            vec![4.0, 16.0, 24.0, 16.0, 4.0],
+            vec![6.0, 24.0, 36.0, 24.0, 6.0],
+            vec![4.0, 16.0, 24.0, 16.0, 4.0],
+            vec![1.0, 4.0, 6.0, 4.0, 1.0],
+        ]).unwrap()
+    }
+
+    pub fn sharpen() -> Self {
+        Self::new(vec![
+            vec![0.0, -1.0, 0.0],
+            vec![-1.0, 5.0, -1.0],
+            vec![0.0, -1.0, 0.0],
+        ]).unwrap()
+    }
+
+    pub fn emboss() -> Self {
+        Self::new(vec![
+            vec![-2.0, -1.0, 0.0],
+            vec![-1.0, 0.0, 1.0],
+            vec![0.0, 1.0, 2.0],
+        ]).unwrap().with_offset(128.0)
+    }
+
+    fn sobel() -> (Self, Self) {
+        let gx = Self::new(vec![
+            vec![-1.0, 0.0, 1.0],
+            vec![-2.0, 0.0, 2.0],
+            vec![-1.0, 0.0, 1.0],
+        ]).unwrap();
+        let gy = Self::new(vec![
+            vec![-1.0, -2.0, -1.0],
+            vec![0.0, 0.0, 0.0],
+            vec![1.0, 2.0, 1.0],
+        ]).unwrap();
+        (gx, gy)
+    }
+
+    /// Raw (unclamped) result of the kernel at a pixel, edges of the frame are extended.
+    fn at(&self, f: &Frame, x: u32, y: u32) -> [f64; 3] {
+        let half = (self.size / 2) as i64;
+        let (w, h) = (f.width() as i64, f.height() as i64);
+        let mut out = [self.offset; 3];
+        for ky in 0..self.size as i64 {
+            let sy = (y as i64 + ky - half).clamp(0, h - 1) as u32;
+            for kx in 0..self.size as i64 {
+                let sx = (x as i64 + kx - half).clamp(0, w - 1) as u32;
+                let weight = self.weights[(ky * self.size as i64 + kx) as usize];
+                if weight == 0.0 { continue; }
+                let p = f.get_pixel(sx, sy);
+                for i in 0..3 {
+                    out[i] += weight * p[i] as f64;
+                }
+            }
+        }
+        out
+    }
+}
+
+fn to_color(c: [f64; 3]) -> Color {
+    Color::from(c.map(|x| x.round().clamp(0.0, 255.0) as u8))
+}
+
+/// Replace the given pixels by the result of `compute`, which reads the frame as it was before.
+/// Rows are processed in parallel.
+fn filter<F>(f: &mut Frame, xs: &[u32], ys: &[u32], compute: F)
+where
+    F: Fn(&Frame, u32, u32) -> Color + Sync,
+{
+    let source: &Frame = f;
+    let rows: Vec<Vec<Color>> = ys
+        .par_iter()
+        .map(|y| xs.iter().map(|x| compute(source, *x, *y)).collect())
+        .collect();
+    for (y, row) in ys.iter().zip(rows) {
+        for (x, c) in xs.iter().zip(row) {
+            f.put_pixel(*x, *y, c);
+        }
+    }
+}
+
+/// Convolve the pixels at the given columns and rows with the kernel.
+pub fn convolve(f: &mut Frame, xs: &[u32], ys: &[u32], kernel: &Kernel) {
+    filter(f, xs, ys, |src, x, y| to_color(kernel.at(src, x, y)));
+}
+
+/// Sobel edge detection, the result is the gradient magnitude of each channel.
+pub fn detect_edges(f: &mut Frame, xs: &[u32], ys: &[u32]) {
+    let (gx, gy) = Kernel::sobel();
+    filter(f, xs, ys, |src, x, y| {
+        let (dx, dy) = (gx.at(src, x, y), gy.at(src, x, y));
+        to_color([0, 1, 2].map(|i| (dx[i] * dx[i] + dy[i] * dy[i]).sqrt()))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all(f: &Frame) -> (Vec<u32>, Vec<u32>) {
+        ((0..f.width()).collect(), (0..f.height()).collect())
+    }
+
+    #[test]
+    fn test_kernel_new() {
+        assert!(Kernel::new(vec![vec![1.0, 1.0], vec![1.0, 1.0]]).is_err());
+        assert!(Kernel::new(vec![vec![1.0, 1.0, 1.0]]).is_err());
+        let k = Kernel::new(vec![vec![2.0]]).unwrap();
+        assert_eq!(k.weights, vec![1.0]);
+    }
+
+    #[test]
+    fn test_convolve() {
+        let c = Color::from([100, 150, 200]);
+        let mut f = Frame::from_pixel(6, 5, c);
+        let (xs, ys) = all(&f);
+        // uniform images are kept by normalized kernels
+        convolve(&mut f, &xs, &ys, &Kernel::gaussian_blur());
+        convolve(&mut f, &xs, &ys, &Kernel::sharpen());
+        assert!(f.pixels().all(|p| *p == c));
+        convolve(&mut f, &xs, &ys, &Kernel::emboss());
+        assert!(f.pixels().all(|p| p.0 == [128, 128, 128]));
+        detect_edges(&mut f, &xs, &ys);
+        assert!(f.pixels().all(|p| p.0 == [0, 0, 0]));
+    }
+
+    #[test]
+    fn test_detect_edges() {
+        // vertical edge between columns 2 and 3
+        let mut f = Frame::from_fn(6, 3, |x, _| if x < 3 { Color::from([0, 0, 0]) } else { Color::from([255, 255, 255]) });
+        let (xs, ys) = all(&f);
+        detect_edges(&mut f, &xs, &ys);
+        assert_eq!(f.get_pixel(0, 1).0, [0, 0, 0]);
+        assert_eq!(f.get_pixel(2, 1).0, [255, 255, 255]);
+        assert_eq!(f.get_pixel(5, 1).0, [0, 0, 0]);
+    }
+}
@@ -1,13 +1,150 @@
 use super::*;
 use rand::{Rng, thread_rng};
-use image::{Pixel, Rgb, RgbImage};
-use crate::variable::{Color, Effect};
+use image::{ImageBuffer, Pixel, Rgb, Rgba};
+use crate::variable::{Color, Effect, Gradient, Mask, color};
+use super::raster::Shape;
+use super::convolution::{self, Kernel};
+
+/// What the inside of a shape is drawn with.
+#[derive(Clone, Copy, Debug)]
+pub enum Fill {
+    Color(Color),
+    Effect(Effect),
+    Gradient(Gradient),
+}
+
+impl Fill {
+    /// Color of the fill at a pixel, effects have no color of their own.
+    pub fn color_at(&self, x: f64, y: f64) -> Option<Color> {
+        match self {
+            Fill::Color(c) => Some(*c),
+            Fill::Gradient(g) => Some(g.color_at(x, y)),
+            Fill::Effect(_) => None,
+        }
+    }
+}
+
+// ************* Canvas impl ************* //
+/// Something that can be painted on pixel by pixel.
+pub trait Canvas {
+    fn size(&self) -> (u32, u32);
+    fn get_color(&self, x: u32, y: u32) -> Color;
+    fn set_color(&mut self, x: u32, y: u32, c: Color);
+
+    /// Paint a pixel partially, `coverage` is the part of the pixel covered (0.0 - 1.0).
+    fn blend_color(&mut self, x: u32, y: u32, c: Color, coverage: f64) {
+        if coverage >= 1.0 {
+            self.set_color(x, y, c);
+        } else if coverage > 0.0 {
+            let blended = color::mix(self.get_color(x, y), c, coverage);
+            self.set_color(x, y, blended);
+        }
+    }
+
+    /// Paint the pixels of a shape with colors given by `color_at`,
+    /// parts outside of the canvas are cut off.
+    fn fill_shape<F: Fn(i32, i32) -> Color>(&mut self, shape: &Shape, color_at: F, antialias: bool) {
+        for (x, y, coverage) in shape.covered_pixels(shape.bounds_within(self.size()), antialias) {
+            self.blend_color(x as u32, y as u32, color_at(x, y), coverage);
+        }
+    }
+
+    /// Fill a rectangle, it wraps around the edges when the bottom right corner is before the top left one.
+    fn fill_rect(&mut self, top_left: (usize,usize), bottom_right: (usize,usize), c: Color) {
+        let (width, height) = self.size();
+        if width == 0 || height == 0 { return; }
+        let xs = span(top_left.0 as u32 % width, bottom_right.0 as u32 % width, width);
+        let ys = span(top_left.1 as u32 % height, bottom_right.1 as u32 % height, height);
+        for y in ys {
+            for x in &xs {
+                self.set_color(*x, y, c);
+            }
+        }
+    }
+
+    /// Draw an image with its top left corner at `(x, y)`, parts outside of the canvas are cut off.
+    /// Pixels are blended according to their alpha.
+    fn draw_image(&mut self, img: &Image, x: i64, y: i64) where Self: Sized {
+        blend_image(self, img, x, y, |_, _| 1.0);
+    }
+
+    /// Draw an image like `draw_image`, its opacity is multiplied by `opacity` (0.0 - 1.0).
+    fn draw_image_with_opacity(&mut self, img: &Image, x: i64, y: i64, opacity: f64) where Self: Sized {
+        blend_image(self, img, x, y, |_, _| opacity);
+    }
+
+    /// Draw an image like `draw_image`, its opacity is multiplied by the mask.
+    fn draw_image_through(&mut self, img: &Image, x: i64, y: i64, mask: &Mask) where Self: Sized {
+        blend_image(self, img, x, y, |cx, cy| mask.coverage(cx as i32, cy as i32));
+    }
+}
+
+/// Blend the image onto the canvas, `opacity` is given the canvas coordinates.
+fn blend_image<C: Canvas, F: Fn(i64, i64) -> f64>(canvas: &mut C, img: &Image, x: i64, y: i64, opacity: F) {
+    let (width, height) = canvas.size();
+    for (ix, iy, p) in img.enumerate_pixels() {
+        let (cx, cy) = (x + ix as i64, y + iy as i64);
+        if cx < 0 || cy < 0 || cx >= width as i64 || cy >= height as i64 {
+            continue;
+        }
+        canvas.blend_color(cx as u32, cy as u32, p.to_rgb(), p[3] as f64 / 255.0 * opacity(cx, cy));
+    }
+}
+
+impl Canvas for Frame {
+    fn size(&self) -> (u32, u32) {
+        self.dimensions()
+    }
+
+    fn get_color(&self, x: u32, y: u32) -> Color {
+        *self.get_pixel(x, y)
+    }
+
+    fn set_color(&mut self, x: u32, y: u32, c: Color) {
+        self.put_pixel(x, y, c);
+    }
+}
+
+impl Canvas for Image {
+    fn size(&self) -> (u32, u32) {
+        self.dimensions()
+    }
+
+    fn get_color(&self, x: u32, y: u32) -> Color {
+        self.get_pixel(x, y).to_rgb()
+    }
+
+    /// The pixel becomes opaque.
+    fn set_color(&mut self, x: u32, y: u32, c: Color) {
+        self.put_pixel(x, y, c.to_rgba());
+    }
+
+    /// Paint over the pixel ("over" compositing), so painting on transparent pixels
+    /// makes them as opaque as the coverage.
+    fn blend_color(&mut self, x: u32, y: u32, c: Color, coverage: f64) {
+        if coverage <= 0.0 {
+            return;
+        }
+        let Rgba([r, g, b, a]) = *self.get_pixel(x, y);
+        let coverage = coverage.min(1.0);
+        let below = a as f64 / 255.0 * (1.0 - coverage);
+        let alpha = coverage + below;
+        let channel = |i: usize, old: u8| ((c[i] as f64 * coverage + old as f64 * below) / alpha).round() as u8;
+        let out = [channel(0, r), channel(1, g), channel(2, b), (alpha * 255.0).round() as u8];
+        self.put_pixel(x, y, Rgba(out));
+    }
+}
 
 // ************* Drawable impl ************* //
 pub trait Drawable {
     fn draw_rect(&mut self, top_left: (usize,usize), bottom_right: (usize,usize), p: Color);
     fn draw_rect_outline(&mut self, top_left: (usize,usize), bottom_right: (usize,usize), p: Color);
     fn draw_effect_rect(&mut self, top_left: (usize,usize), bottom_right: (usize,usize), e: Effect);
+    fn draw_shape(&mut self, shape: &Shape, fill: Fill, antialias: bool);
+    fn draw_effect(&mut self, e: Effect);
+    fn draw_kernel_rect(&mut self, top_left: (usize,usize), bottom_right: (usize,usize), k: &Kernel);
+    fn draw_kernel(&mut self, k: &Kernel);
+    fn draw_through(&mut self, fill: Fill, mask: &Mask);
 }
 
 impl Drawable for Frame {
@@ -59,10 +196,74 @@ impl Drawable for Frame {
         let r = bottom_right.0 as u32 % width;
         let t = top_left.1 as u32 % height;
         let b = bottom_right.1 as u32 % height;
-        match e {
-            Effect::Blur => blur(self, l, r, t, b),
-            Effect::Random => randomize(self, l, r, t, b),
-            Effect::Inverse => inverse(self, l, r, t, b),
+        apply_effect(self, l, r, t, b, e);
+    }
+
+    /// Applies the effect to the whole image.
+    fn draw_effect(&mut self, e: Effect) {
+        let (width, height) = self.dimensions();
+        apply_effect(self, 0, width, 0, height, e);
+    }
+
+    /// Convolves the rectangle with the kernel.
+    fn draw_kernel_rect(&mut self, top_left: (usize,usize), bottom_right: (usize,usize), k: &Kernel) {
+        let width = self.width();
+        let height = self.height();
+        let xs = span(top_left.0 as u32 % width, bottom_right.0 as u32 % width, width);
+        let ys = span(top_left.1 as u32 % height, bottom_right.1 as u32 % height, height);
+        convolution::convolve(self, &xs, &ys, k);
+    }
+
+    /// Convolves the whole image with the kernel.
+    fn draw_kernel(&mut self, k: &Kernel) {
+        let (width, height) = self.dimensions();
+        convolution::convolve(self, &span(0, width, width), &span(0, height, height), k);
+    }
+
+    /// Draws the fill with the opacity of the mask, effects are computed on the whole frame
+    /// (so they can use pixels outside of the mask) and then blended in.
+    fn draw_through(&mut self, fill: Fill, mask: &Mask) {
+        let (l, t, r, b) = mask.region(self.width(), self.height());
+        if l >= r || t >= b { return; }
+        let effected = if let Fill::Effect(e) = fill {
+            let mut copy = self.clone();
+            apply_effect(&mut copy, l, r, t, b, e);
+            Some(copy)
+        } else {
+            None
+        };
+        for y in t..b {
+            for x in l..r {
+                let p = match &effected {
+                    Some(f) => *f.get_pixel(x, y),
+                    None => fill.color_at(x as f64, y as f64).unwrap(),
+                };
+                self.blend_color(x, y, p, mask.coverage(x as i32, y as i32));
+            }
+        }
+    }
+
+    /// Draws a shape, parts outside of the frame are cut off.
+    /// With `antialias`, partially covered pixels are blended with the fill.
+    fn draw_shape(&mut self, shape: &Shape, fill: Fill, antialias: bool) {
+        match fill {
+            Fill::Color(p) => self.fill_shape(shape, |_, _| p, antialias),
+            Fill::Gradient(g) => self.fill_shape(shape, |x, y| g.color_at(x as f64, y as f64), antialias),
+            Fill::Effect(e) => {
+                // apply the effect to a copy of the bounding box, then take the pixels inside the shape
+                let bounds = shape.bounds_within(self.size());
+                let (l, t, r, b) = bounds;
+                if l == r || t == b { return; }
+                let mut patch = Frame::from_fn((r - l) as u32, (b - t) as u32, |x, y| {
+                    *self.get_pixel(l as u32 + x, t as u32 + y)
+                });
+                let (pw, ph) = patch.dimensions();
+                apply_effect(&mut patch, 0, pw, 0, ph, e);
+                for (x, y, coverage) in shape.covered_pixels(bounds, antialias) {
+                    let p = *patch.get_pixel((x - l) as u32, (y - t) as u32);
+                    self.blend_color(x as u32, y as u32, p, coverage);
+                }
+            }
         }
     }
 
@@ -109,8 +310,82 @@ impl Drawable for Frame {
     }
 }
 
-fn blur(_f: &mut Frame, _l: u32, _r: u32, _t: u32, _b: u32) {
-    todo!();
+/// Drawing on images: colors make the pixels opaque, effects only change the colors
+/// (the transparency is kept).
+impl Drawable for Image {
+    fn draw_rect(&mut self, top_left: (usize,usize), bottom_right: (usize,usize), p: Color) {
+        self.fill_rect(top_left, bottom_right, p);
+    }
+
+    fn draw_rect_outline(&mut self, top_left: (usize,usize), bottom_right: (usize,usize), p: Color) {
+        let ((l, t), (r, b)) = (top_left, bottom_right);
+        self.fill_rect((l, t), (r, t + 1), p);
+        self.fill_rect((l, b), (r, b + 1), p);
+        self.fill_rect((l, t), (l + 1, b), p);
+        self.fill_rect((r, t), (r + 1, b), p);
+    }
+
+    fn draw_effect_rect(&mut self, top_left: (usize,usize), bottom_right: (usize,usize), e: Effect) {
+        with_colors(self, |f| f.draw_effect_rect(top_left, bottom_right, e));
+    }
+
+    fn draw_shape(&mut self, shape: &Shape, fill: Fill, antialias: bool) {
+        match fill {
+            Fill::Color(p) => self.fill_shape(shape, |_, _| p, antialias),
+            Fill::Gradient(g) => self.fill_shape(shape, |x, y| g.color_at(x as f64, y as f64), antialias),
+            Fill::Effect(_) => with_colors(self, |f| f.draw_shape(shape, fill, antialias)),
+        }
+    }
+
+    fn draw_effect(&mut self, e: Effect) {
+        with_colors(self, |f| f.draw_effect(e));
+    }
+
+    fn draw_kernel_rect(&mut self, top_left: (usize,usize), bottom_right: (usize,usize), k: &Kernel) {
+        with_colors(self, |f| f.draw_kernel_rect(top_left, bottom_right, k));
+    }
+
+    fn draw_kernel(&mut self, k: &Kernel) {
+        with_colors(self, |f| f.draw_kernel(k));
+    }
+
+    fn draw_through(&mut self, fill: Fill, mask: &Mask) {
+        if let Fill::Effect(_) = fill {
+            with_colors(self, |f| f.draw_through(fill, mask));
+            return;
+        }
+        let (l, t, r, b) = mask.region(self.width(), self.height());
+        for y in t..b {
+            for x in l..r {
+                if let Some(p) = fill.color_at(x as f64, y as f64) {
+                    self.blend_color(x, y, p, mask.coverage(x as i32, y as i32));
+                }
+            }
+        }
+    }
+}
+
+/// Run `draw` on the colors of the image, its transparency stays the same.
+fn with_colors<F: FnOnce(&mut Frame)>(img: &mut Image, draw: F) {
+    let mut colors = Frame::from_fn(img.width(), img.height(), |x, y| img.get_pixel(x, y).to_rgb());
+    draw(&mut colors);
+    for (p, c) in img.pixels_mut().zip(colors.pixels()) {
+        *p = Rgba([c[0], c[1], c[2], p[3]]);
+    }
+}
+
+fn apply_effect(f: &mut Frame, l: u32, r: u32, t: u32, b: u32, e: Effect) {
+    let (width, height) = f.dimensions();
+    match e {
+        Effect::Blur => convolution::convolve(f, &span(l, r, width), &span(t, b, height), &Kernel::gaussian_blur()),
+        Effect::Sharpen => convolution::convolve(f, &span(l, r, width), &span(t, b, height), &Kernel::sharpen()),
+        Effect::Emboss => convolution::convolve(f, &span(l, r, width), &span(t, b, height), &Kernel::emboss()),
+        Effect::EdgeDetect => convolution::detect_edges(f, &span(l, r, width), &span(t, b, height)),
+        Effect::Pixelate(n) => pixelate(f, &span(l, r, width), &span(t, b, height), n, false),
+        Effect::Mosaic(n) => pixelate(f, &span(l, r, width), &span(t, b, height), n, true),
+        Effect::Random => randomize(f, l, r, t, b),
+        e => for_each_in_span(f, l, r, t, b, |p| *p = e.grade(*p)),
+    }
 }
 
 fn randomize(f: &mut Frame, l: u32, r: u32, t: u32, b: u32) {
@@ -119,10 +394,10 @@ fn randomize(f: &mut Frame, l: u32, r: u32, t: u32, b: u32) {
     let _rng = thread_rng();
     if l <= r {
         let mut rng = _rng.clone();
-        let mut x_gen = std::iter::repeat_with(move || rng.gen_range(l..=r));
+        let mut x_gen = std::iter::repeat_with(move || rng.gen_range(l..r));
         if t <= b {
             let mut rng = _rng.clone();
-            let mut y_gen = std::iter::repeat_with(move || rng.gen_range(t..=b));
+            let mut y_gen = std::iter::repeat_with(move || rng.gen_range(t..b));
             for y in t..b {
                 for x in l..r {
                     let p = f.get_pixel(x_gen.next().unwrap(), y_gen.next().unwrap());
@@ -150,7 +425,7 @@ fn randomize(f: &mut Frame, l: u32, r: u32, t: u32, b: u32) {
         let mut x_gen = std::iter::repeat_with(move || rng.gen_range(r..=l+width) % width);
         if t <= b {
             let mut rng = _rng.clone();
-            let mut y_gen = std::iter::repeat_with(move || rng.gen_range(t..=b));
+            let mut y_gen = std::iter::repeat_with(move || rng.gen_range(t..b));
             for y in t..b {
                 for x in 0..r { 
                     let p = f.get_pixel(x_gen.next().unwrap(), y_gen.next().unwrap());
@@ -188,43 +463,104 @@ fn randomize(f: &mut Frame, l: u32, r: u32, t: u32, b: u32) {
     }
 }
 
-fn inverse(f: &mut Frame, l: u32, r: u32, t: u32, b: u32) {
-    fn inverse_pixel(f: &mut Frame, x: u32, y: u32) {
-        f.get_pixel_mut(x, y).invert();
-    }
-
-    let width = f.width();
-    let height = f.height();
-    if l <= r {
-        if t <= b {
-            for y in t..b {
-                for x in l..r {
-                    inverse_pixel(f, x, y);
+/// Replace blocks of `size` x `size` pixels by their average color, blocks start at the top left
+/// corner of the region. With `grout`, the last row and column of each block are darkened.
+fn pixelate(f: &mut Frame, xs: &[u32], ys: &[u32], size: i32, grout: bool) {
+    let size = size.max(1) as usize;
+    for block_ys in ys.chunks(size) {
+        for block_xs in xs.chunks(size) {
+            let mut sum = [0u64; 3];
+            for y in block_ys {
+                for x in block_xs {
+                    let p = f.get_pixel(*x, *y);
+                    for i in 0..3 { sum[i] += p[i] as u64; }
                 }
             }
-        } else {
-            for y in 0..b {
-                for x in l..r { inverse_pixel(f, x, y); }
-            }
-            for y in t..height {
-                for x in l..r { inverse_pixel(f, x, y); }
+            let count = (block_xs.len() * block_ys.len()) as f64;
+            let avg = Color::from(sum.map(|s| (s as f64 / count).round() as u8));
+            let dark = color::mix(avg, Color::from([0,0,0]), 0.5);
+            for (j, y) in block_ys.iter().enumerate() {
+                for (i, x) in block_xs.iter().enumerate() {
+                    let edge = grout && size > 2 && (i == size - 1 || j == size - 1);
+                    f.put_pixel(*x, *y, if edge { dark } else { avg });
+                }
             }
         }
-    } else {
-        if t <= b {
-            for y in t..b {
-                for x in 0..r { inverse_pixel(f, x, y); }
-                for x in l..width { inverse_pixel(f, x, y); }
-            }
-        } else {
-            for y in 0..b {
-                for x in 0..r { inverse_pixel(f, x, y); }
-                for x in l..width { inverse_pixel(f, x, y); }
-            }
-            for y in t..height {
-                for x in 0..r { inverse_pixel(f, x, y); }
-                for x in l..width { inverse_pixel(f, x, y); }
-            }
+    }
+}
+
+/// Coordinates from `from` to `to` (exclusive), wrapping around `len` when `to` is before `from`.
+fn span(from: u32, to: u32, len: u32) -> Vec<u32> {
+    if from <= to { (from..to).collect() } else { (0..to).chain(from..len).collect() }
+}
+
+/// Call `g` on every pixel of the region, the region wraps around the edges of the frame
+/// when its right (bottom) edge is before its left (top) edge.
+fn for_each_in_span<G: FnMut(&mut Color)>(f: &mut Frame, l: u32, r: u32, t: u32, b: u32, mut g: G) {
+    let xs = span(l, r, f.width());
+    let ys = span(t, b, f.height());
+    for y in ys {
+        for x in &xs {
+            g(f.get_pixel_mut(*x, y));
+        }
+    }
+}
+
+// ************* Samplable impl ************* //
+/// Reading the image content.
+/// Positions outside of the image wrap around (same as when drawing rectangles),
+/// regions are clipped to the image.
+pub trait Samplable {
+    fn color_at(&self, x: i32, y: i32) -> Color;
+    fn average_color(&self, top_left: (i32,i32), bottom_right: (i32,i32)) -> Color;
+    fn histogram(&self, top_left: (i32,i32), bottom_right: (i32,i32)) -> [u32; 256];
+}
+
+impl<P> Samplable for ImageBuffer<P, Vec<u8>>
+where
+    P: Pixel<Subpixel = u8>,
+{
+    fn color_at(&self, x: i32, y: i32) -> Color {
+        let x = x.rem_euclid(self.width() as i32) as u32;
+        let y = y.rem_euclid(self.height() as i32) as u32;
+        self.get_pixel(x, y).to_rgb()
+    }
+
+    fn average_color(&self, top_left: (i32,i32), bottom_right: (i32,i32)) -> Color {
+        let mut sum = [0u64; 3];
+        let mut count = 0;
+        for_each_in_region(self, top_left, bottom_right, |p| {
+            for i in 0..3 { sum[i] += p[i] as u64; }
+            count += 1;
+        });
+        if count == 0 {
+            return Color::from([0,0,0]);
+        }
+        Color::from(sum.map(|s| (s as f64 / count as f64).round() as u8))
+    }
+
+    fn histogram(&self, top_left: (i32,i32), bottom_right: (i32,i32)) -> [u32; 256] {
+        let mut bins = [0; 256];
+        for_each_in_region(self, top_left, bottom_right, |p| {
+            bins[color::luminance(p).round() as usize] += 1;
+        });
+        bins
+    }
+}
+
+/// Iterate over pixels of the region clipped to the image (alpha is ignored).
+fn for_each_in_region<P, F>(img: &ImageBuffer<P, Vec<u8>>, top_left: (i32,i32), bottom_right: (i32,i32), mut f: F)
+where
+    P: Pixel<Subpixel = u8>,
+    F: FnMut(Color),
+{
+    let l = top_left.0.clamp(0, img.width() as i32) as u32;
+    let r = bottom_right.0.clamp(0, img.width() as i32) as u32;
+    let t = top_left.1.clamp(0, img.height() as i32) as u32;
+    let b = bottom_right.1.clamp(0, img.height() as i32) as u32;
+    for y in t..b {
+        for x in l..r {
+            f(img.get_pixel(x, y).to_rgb());
         }
     }
 }
@@ -237,90 +573,151 @@ pub trait Extendable {
     fn prepend_row(&mut self, row: &[Rgb<u8>]);
 }
 
-impl Extendable for Frame {
+/// Raw data of opaque pixels.
+fn opaque_raw(pixels: &[Rgb<u8>]) -> Vec<u8> {
+    pixels.iter().flat_map(|p| p.to_rgba().0).collect()
+}
+
+impl Extendable for Image {
     /// Append a column to the right edge of the image.
     fn append_column(&mut self, column: &[Rgb<u8>]) {
         let (width, height) = self.dimensions();
         if width == 0 {
-            let raw: Vec<u8> = column.iter().flat_map(|p| p.0).collect();
-            *self = RgbImage::from_raw(1, column.len() as u32, raw).unwrap();
+            *self = Image::from_raw(1, column.len() as u32, opaque_raw(column)).unwrap();
             return
         }
         assert_eq!(column.len() as u32, height, "column length must match image height");
 
         let old_raw = self.as_raw();
-        let stride = width as usize * 3;
-        let mut new_raw = Vec::with_capacity((width + 1) as usize * height as usize * 3);
+        let stride = width as usize * 4;
+        let mut new_raw = Vec::with_capacity((width + 1) as usize * height as usize * 4);
 
         for y in 0..height as usize {
             new_raw.extend_from_slice(&old_raw[y * stride..(y + 1) * stride]);
-            new_raw.extend_from_slice(&column[y].0);
+            new_raw.extend_from_slice(&column[y].to_rgba().0);
         }
 
-        *self = RgbImage::from_raw(width + 1, height, new_raw).unwrap()
+        *self = Image::from_raw(width + 1, height, new_raw).unwrap()
     }
 
     /// Prepend a column to the left edge of the image.
     fn prepend_column(&mut self, column: &[Rgb<u8>]) {
         let (width, height) = self.dimensions();
         if width == 0 {
-            let raw: Vec<u8> = column.iter().flat_map(|p| p.0).collect();
-            *self = RgbImage::from_raw(1, column.len() as u32, raw).unwrap();
+            *self = Image::from_raw(1, column.len() as u32, opaque_raw(column)).unwrap();
             return
         }
         assert_eq!(column.len() as u32, height, "column length must match image height");
 
         let old_raw = self.as_raw();
-        let stride = width as usize * 3;
-        let mut new_raw = Vec::with_capacity((width + 1) as usize * height as usize * 3);
+        let stride = width as usize * 4;
+        let mut new_raw = Vec::with_capacity((width + 1) as usize * height as usize * 4);
 
         for y in 0..height as usize {
-            new_raw.extend_from_slice(&column[y].0);
+            new_raw.extend_from_slice(&column[y].to_rgba().0);
             new_raw.extend_from_slice(&old_raw[y * stride..(y + 1) * stride]);
         }
 
-        *self = RgbImage::from_raw(width + 1, height, new_raw).unwrap()
+        *self = Image::from_raw(width + 1, height, new_raw).unwrap()
     }
 
     /// Append a row to the bottom edge of the image.
     fn append_row(&mut self, row: &[Rgb<u8>]) {
         let (width, height) = self.dimensions();
         if height == 0 {
-            let raw: Vec<u8> = row.iter().flat_map(|p| p.0).collect();
-            *self = RgbImage::from_raw(row.len() as u32, 1, raw).unwrap();
+            *self = Image::from_raw(row.len() as u32, 1, opaque_raw(row)).unwrap();
             return
         }
         assert_eq!(row.len() as u32, width, "row length must match image width");
 
         let old_raw = self.as_raw();
-        let mut new_raw = Vec::with_capacity(width as usize * (height + 1) as usize * 3);
+        let mut new_raw = Vec::with_capacity(width as usize * (height + 1) as usize * 4);
 
         new_raw.extend_from_slice(old_raw);
-        for pixel in row {
-            new_raw.extend_from_slice(&pixel.0);
-        }
+        new_raw.extend(opaque_raw(row));
 
-        *self = RgbImage::from_raw(width, height + 1, new_raw).unwrap()
+        *self = Image::from_raw(width, height + 1, new_raw).unwrap()
     }
 
     /// Prepend a row to the top edge of the image.
     fn prepend_row(&mut self, row: &[Rgb<u8>]) {
         let (width, height) = self.dimensions();
         if height == 0 {
-            let raw: Vec<u8> = row.iter().flat_map(|p| p.0).collect();
-            *self = RgbImage::from_raw(row.len() as u32, 1, raw).unwrap();
+            *self = Image::from_raw(row.len() as u32, 1, opaque_raw(row)).unwrap();
             return
         }
         assert_eq!(row.len() as u32, width, "row length must match image width");
 
         let old_raw = self.as_raw();
-        let mut new_raw = Vec::with_capacity(width as usize * (height + 1) as usize * 3);
+        let mut new_raw = Vec::with_capacity(width as usize * (height + 1) as usize * 4);
 
-        for pixel in row {
-            new_raw.extend_from_slice(&pixel.0);
-        }
+        new_raw.extend(opaque_raw(row));
         new_raw.extend_from_slice(old_raw);
 
-        *self = RgbImage::from_raw(width, height + 1, new_raw).unwrap()
+        *self = Image::from_raw(width, height + 1, new_raw).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pixelate() {
+        let mut img = Frame::from_fn(4, 4, |x, _| Color::from([if x % 2 == 0 { 0 } else { 200 }; 3]));
+        img.draw_effect(Effect::Pixelate(2));
+        assert!(img.pixels().all(|p| p.0 == [100, 100, 100]));
+        let mut img = Frame::from_pixel(6, 6, Color::from([200,200,200]));
+        img.draw_effect(Effect::Mosaic(3));
+        assert_eq!(img.get_pixel(0, 0).0, [200, 200, 200]);
+        assert_eq!(img.get_pixel(2, 0).0, [100, 100, 100]);
+        assert_eq!(img.get_pixel(3, 3).0, [200, 200, 200]);
+    }
+
+    #[test]
+    fn test_draw_image() {
+        let mut img = Image::from_pixel(2, 1, Rgba([255, 0, 0, 255]));
+        img.put_pixel(1, 0, Rgba([0, 0, 255, 0]));
+        let mut f = Frame::from_pixel(3, 2, Color::from([0, 0, 0]));
+        f.draw_image(&img, 2, 1);
+        assert_eq!(f.get_pixel(2, 1).0, [255, 0, 0]);
+        assert_eq!(f.get_pixel(0, 0).0, [0, 0, 0]);
+        // painting on a transparent pixel makes it opaque
+        img.blend_color(1, 0, Color::from([0, 255, 0]), 0.5);
+        assert_eq!(*img.get_pixel(1, 0), Rgba([0, 255, 0, 128]));
+        img.blend_color(1, 0, Color::from([0, 255, 0]), 1.0);
+        assert_eq!(img.get_pixel(1, 0)[3], 255);
+    }
+
+    #[test]
+    fn test_sampling() {
+        let mut img = Frame::from_pixel(4, 2, Color::from([0,0,0]));
+        for x in 0..2 {
+            for y in 0..2 {
+                img.put_pixel(x, y, Color::from([255,255,255]));
+            }
+        }
+        assert_eq!(img.color_at(0, 0), Color::from([255,255,255]));
+        assert_eq!(img.color_at(-1, 0), Color::from([0,0,0]));
+        assert_eq!(img.average_color((0,0), (4,2)), Color::from([128,128,128]));
+        assert_eq!(img.average_color((-5,-5), (2,2)), Color::from([255,255,255]));
+        let bins = img.histogram((0,0), (10,10));
+        assert_eq!((bins[0], bins[255]), (4, 4));
+    }
+
+    #[test]
+    fn test_draw_shape_is_cut_off() {
+        let white = Color::from([255,255,255]);
+        // only the pixels of the frame are visited, not the whole bounding box
+        let mut f = Frame::from_pixel(10, 10, Color::from([0,0,0]));
+        let huge = Shape::Ellipse { center: (5.0, 5.0), radii: (1e9, 1e9), outline: None };
+        f.draw_shape(&huge, Fill::Color(white), true);
+        assert!(f.pixels().all(|p| *p == white));
+        // a circle over the right edge does not show up on the left
+        let mut f = Frame::from_pixel(10, 10, Color::from([0,0,0]));
+        let circle = Shape::Ellipse { center: (10.0, 5.0), radii: (3.0, 3.0), outline: None };
+        f.draw_shape(&circle, Fill::Color(white), false);
+        assert_eq!(*f.get_pixel(9, 5), white);
+        assert!((0..10).all(|y| f.get_pixel(0, y).0 == [0,0,0]));
     }
 }
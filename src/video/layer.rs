@@ -0,0 +1,211 @@
+use image::{Pixel, Rgba};
+
+use super::{Canvas, Drawable, Fill, Frame, Image, Kernel, raster::Shape};
+use crate::variable::{Color, Effect, Mask};
+
+/// How the colors of a layer are combined with the colors below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendMode {
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Add,
+    Difference,
+}
+
+impl BlendMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "normal" => Some(Self::Normal),
+            "multiply" => Some(Self::Multiply),
+            "screen" => Some(Self::Screen),
+            "overlay" => Some(Self::Overlay),
+            "add" => Some(Self::Add),
+            "difference" => Some(Self::Difference),
+            _ => None,
+        }
+    }
+
+    /// Blend a channel of the layer (`top`) with a channel below it (`bottom`).
+    fn blend(&self, bottom: u8, top: u8) -> u8 {
+        let (b, t) = (bottom as f64 / 255.0, top as f64 / 255.0);
+        let out = match self {
+            Self::Normal => t,
+            Self::Multiply => b * t,
+            Self::Screen => 1.0 - (1.0 - b) * (1.0 - t),
+            Self::Overlay => if b < 0.5 { 2.0 * b * t } else { 1.0 - 2.0 * (1.0 - b) * (1.0 - t) },
+            Self::Add => (b + t).min(1.0),
+            Self::Difference => (b - t).abs(),
+        };
+        (out * 255.0).round() as u8
+    }
+}
+
+/// Transparent image drawn over the frame once the frame is finished.
+#[derive(Debug, Clone)]
+pub struct Layer {
+    pub name: String,
+    pub image: Image,
+    /// Layers with higher z-index are drawn over the ones with lower z-index.
+    pub z_index: i32,
+    /// Opacity of the whole layer (0.0 - 1.0).
+    pub opacity: f64,
+    pub blend_mode: BlendMode,
+}
+
+impl Layer {
+    pub fn new(name: String, width: u32, height: u32) -> Self {
+        Self { name, image: Image::new(width, height), z_index: 0, opacity: 1.0, blend_mode: BlendMode::Normal }
+    }
+
+    /// Make the layer fully transparent, ready for the next frame.
+    pub fn clear(&mut self) {
+        self.image.pixels_mut().for_each(|p| *p = Rgba([0, 0, 0, 0]));
+    }
+
+    /// Draw the layer over the frame.
+    pub fn composite(&self, frame: &mut Frame) {
+        for (x, y, p) in self.image.enumerate_pixels() {
+            if p[3] == 0 || x >= frame.width() || y >= frame.height() {
+                continue;
+            }
+            let below = *frame.get_pixel(x, y);
+            let blended = below.map2(&p.to_rgb(), |b, t| self.blend_mode.blend(b, t));
+            frame.blend_color(x, y, blended, p[3] as f64 / 255.0 * self.opacity);
+        }
+    }
+}
+
+/// What drawing operations draw on, the frame itself or a layer.
+pub enum Target<'a> {
+    Frame(&'a mut Frame),
+    Layer(&'a mut Image),
+}
+
+impl Canvas for Target<'_> {
+    fn size(&self) -> (u32, u32) {
+        match self {
+            Target::Frame(f) => f.size(),
+            Target::Layer(l) => l.size(),
+        }
+    }
+
+    fn get_color(&self, x: u32, y: u32) -> Color {
+        match self {
+            Target::Frame(f) => f.get_color(x, y),
+            Target::Layer(l) => l.get_color(x, y),
+        }
+    }
+
+    fn set_color(&mut self, x: u32, y: u32, c: Color) {
+        match self {
+            Target::Frame(f) => f.set_color(x, y, c),
+            Target::Layer(l) => l.set_color(x, y, c),
+        }
+    }
+
+    fn blend_color(&mut self, x: u32, y: u32, c: Color, coverage: f64) {
+        match self {
+            Target::Frame(f) => f.blend_color(x, y, c, coverage),
+            Target::Layer(l) => l.blend_color(x, y, c, coverage),
+        }
+    }
+}
+
+impl Drawable for Target<'_> {
+    fn draw_rect(&mut self, top_left: (usize,usize), bottom_right: (usize,usize), p: Color) {
+        match self {
+            Target::Frame(f) => f.draw_rect(top_left, bottom_right, p),
+            Target::Layer(l) => l.draw_rect(top_left, bottom_right, p),
+        }
+    }
+
+    fn draw_rect_outline(&mut self, top_left: (usize,usize), bottom_right: (usize,usize), p: Color) {
+        match self {
+            Target::Frame(f) => f.draw_rect_outline(top_left, bottom_right, p),
+            Target::Layer(l) => l.draw_rect_outline(top_left, bottom_right, p),
+        }
+    }
+
+    fn draw_effect_rect(&mut self, top_left: (usize,usize), bottom_right: (usize,usize), e: Effect) {
+        match self {
+            Target::Frame(f) => f.draw_effect_rect(top_left, bottom_right, e),
+            Target::Layer(l) => l.draw_effect_rect(top_left, bottom_right, e),
+        }
+    }
+
+    fn draw_shape(&mut self, shape: &Shape, fill: Fill, antialias: bool) {
+        match self {
+            Target::Frame(f) => f.draw_shape(shape, fill, antialias),
+            Target::Layer(l) => l.draw_shape(shape, fill, antialias),
+        }
+    }
+
+    fn draw_effect(&mut self, e: Effect) {
+        match self {
+            Target::Frame(f) => f.draw_effect(e),
+            Target::Layer(l) => l.draw_effect(e),
+        }
+    }
+
+    fn draw_kernel_rect(&mut self, top_left: (usize,usize), bottom_right: (usize,usize), k: &Kernel) {
+        match self {
+            Target::Frame(f) => f.draw_kernel_rect(top_left, bottom_right, k),
+            Target::Layer(l) => l.draw_kernel_rect(top_left, bottom_right, k),
+        }
+    }
+
+    fn draw_kernel(&mut self, k: &Kernel) {
+        match self {
+            Target::Frame(f) => f.draw_kernel(k),
+            Target::Layer(l) => l.draw_kernel(k),
+        }
+    }
+
+    fn draw_through(&mut self, fill: Fill, mask: &Mask) {
+        match self {
+            Target::Frame(f) => f.draw_through(fill, mask),
+            Target::Layer(l) => l.draw_through(fill, mask),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_composite() {
+        let gray = Color::from([100, 100, 100]);
+        let mut layer = Layer::new("l".to_string(), 2, 1);
+        layer.image.put_pixel(0, 0, Rgba([200, 200, 200, 255]));
+        let mut f = Frame::from_pixel(2, 1, gray);
+        layer.composite(&mut f);
+        assert_eq!(*f.get_pixel(0, 0), Color::from([200, 200, 200]));
+        assert_eq!(*f.get_pixel(1, 0), gray);
+
+        layer.opacity = 0.5;
+        layer.blend_mode = BlendMode::Difference;
+        let mut f = Frame::from_pixel(2, 1, gray);
+        layer.composite(&mut f);
+        assert_eq!(*f.get_pixel(0, 0), Color::from([100, 100, 100]));
+        layer.blend_mode = BlendMode::Add;
+        let mut f = Frame::from_pixel(2, 1, gray);
+        layer.composite(&mut f);
+        assert_eq!(*f.get_pixel(0, 0), Color::from([178, 178, 178]));
+
+        layer.clear();
+        assert!(layer.image.pixels().all(|p| p[3] == 0));
+    }
+
+    #[test]
+    fn test_draw_on_layer() {
+        let mut img = Image::new(4, 4);
+        let mut target = Target::Layer(&mut img);
+        target.draw_rect((1, 1), (3, 3), Color::from([255, 0, 0]));
+        target.draw_effect(Effect::Inverse);
+        assert_eq!(*img.get_pixel(1, 1), Rgba([0, 255, 255, 255]));
+        assert_eq!(img.get_pixel(0, 0)[3], 0);
+    }
+}
@@ -1,11 +1,31 @@
 mod reader;
 mod writer;
 mod image_processing;
+pub mod raster;
+mod transform;
+mod camera;
+mod convolution;
+mod chroma;
+pub mod transition;
+mod timeline;
+mod layer;
+mod text;
+mod subtitles;
 
 pub type Frame = image::RgbImage;
+/// Image values of programs, they have an alpha channel unlike frames.
+pub type Image = image::RgbaImage;
 
 use ffmpeg_next::Rational;
 pub use writer::{Video, VideoWriter};
-pub use reader::VideoReader;
-pub use image_processing::{Drawable, Extendable};
+pub use reader::{VideoReader, VideoInfo};
+pub use image_processing::{Canvas, Drawable, Extendable, Samplable, Fill};
+pub use transform::Transformable;
+pub use camera::CameraTransform;
+pub use convolution::Kernel;
+pub use chroma::Keyable;
+pub use timeline::Timeline;
+pub use layer::{Layer, BlendMode, Target};
+pub use subtitles::{Subtitles, SubtitleStyle, parse_timestamp};
+pub use text::Font;
 pub use ffmpeg_next::format::input as get_input;
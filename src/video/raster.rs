@@ -0,0 +1,295 @@
+//! Rasterization of geometric shapes.
+//!
+//! Shapes live in continuous coordinates, pixel `(x,y)` spans the area `[x,x+1) x [y,y+1)`
+//! and is covered by a shape if its center is.
+
+/// Number of samples per pixel along each axis, used for anti-aliasing.
+const SUBSAMPLES: usize = 4;
+
+/// A point in continuous (sub-pixel) coordinates.
+pub type Point = (f64, f64);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    /// Straight line with butt ends.
+    Line { from: Point, to: Point, width: f64 },
+    /// Ellipse (a circle when both radii are the same), optionally only its outline of given width.
+    Ellipse { center: Point, radii: (f64, f64), outline: Option<f64> },
+    /// Filled polygon, self-intersections are handled with the even-odd rule.
+    Polygon(Vec<Point>),
+    /// Connected lines, `closed` also connects the last point with the first one.
+    Polyline { points: Vec<Point>, width: f64, closed: bool },
+    /// Rectangle with rounded corners, optionally only its outline (drawn inside the rectangle).
+    RoundedRect { top_left: Point, bottom_right: Point, radius: f64, outline: Option<f64> },
+}
+
+impl Shape {
+    /// Check whether the point `p` lies inside the shape.
+    pub fn contains(&self, p: Point) -> bool {
+        match self {
+            Shape::Line { from, to, width } => in_segment(p, *from, *to, *width),
+            Shape::Ellipse { center, radii, outline } => {
+                let (dx, dy) = (p.0 - center.0, p.1 - center.1);
+                match outline {
+                    None => in_ellipse(dx, dy, radii.0, radii.1),
+                    Some(w) => {
+                        let h = w / 2.0;
+                        in_ellipse(dx, dy, radii.0 + h, radii.1 + h)
+                            && !in_ellipse(dx, dy, radii.0 - h, radii.1 - h)
+                    }
+                }
+            }
+            Shape::Polygon(points) => in_polygon(p, points),
+            Shape::Polyline { points, width, closed } => {
+                let segments = points.windows(2).map(|w| (w[0], w[1]));
+                let closing = match (closed, points.first(), points.last()) {
+                    (true, Some(first), Some(last)) if points.len() > 2 => Some((*last, *first)),
+                    _ => None,
+                };
+                let r = width / 2.0;
+                segments.chain(closing).any(|(a, b)| in_segment(p, a, b, *width))
+                    // round joins, so that thick polylines do not have gaps in corners
+                    || points.iter().any(|q| distance(p, *q) <= r)
+            }
+            Shape::RoundedRect { top_left, bottom_right, radius, outline } => {
+                let d = rounded_rect_distance(p, *top_left, *bottom_right, *radius);
+                match outline {
+                    None => d < 0.0,
+                    Some(w) => d < 0.0 && d >= -w,
+                }
+            }
+        }
+    }
+
+    /// Pixels which may be covered by the shape, as `(left, top, right, bottom)`,
+    /// right and bottom are exclusive.
+    pub fn bounds(&self) -> (i32, i32, i32, i32) {
+        let (min, max, pad) = match self {
+            Shape::Line { from, to, width } => {
+                let (min, max) = extent(&[*from, *to]);
+                (min, max, width / 2.0)
+            }
+            Shape::Ellipse { center, radii, outline } => {
+                let pad = outline.unwrap_or(0.0) / 2.0;
+                let min = (center.0 - radii.0, center.1 - radii.1);
+                let max = (center.0 + radii.0, center.1 + radii.1);
+                (min, max, pad)
+            }
+            Shape::Polygon(points) => {
+                let (min, max) = extent(points);
+                (min, max, 0.0)
+            }
+            Shape::Polyline { points, width, .. } => {
+                let (min, max) = extent(points);
+                (min, max, width / 2.0)
+            }
+            Shape::RoundedRect { top_left, bottom_right, .. } => (*top_left, *bottom_right, 0.0),
+        };
+        (
+            ((min.0 - pad).floor() as i32).saturating_sub(1),
+            ((min.1 - pad).floor() as i32).saturating_sub(1),
+            ((max.0 + pad).ceil() as i32).saturating_add(1),
+            ((max.1 + pad).ceil() as i32).saturating_add(1),
+        )
+    }
+
+    /// Bounds of the shape cut to a canvas of the given size, empty when the shape misses the canvas.
+    pub fn bounds_within(&self, (width, height): (u32, u32)) -> (i32, i32, i32, i32) {
+        let (l, t, r, b) = self.bounds();
+        let (width, height) = (width.min(i32::MAX as u32) as i32, height.min(i32::MAX as u32) as i32);
+        let (l, t) = (l.clamp(0, width), t.clamp(0, height));
+        (l, t, r.clamp(l, width), b.clamp(t, height))
+    }
+
+    /// All pixels covered by the shape.
+    pub fn pixels(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        let (l, t, r, b) = self.bounds();
+        (t..b)
+            .flat_map(move |y| (l..r).map(move |x| (x, y)))
+            .filter(|(x, y)| self.contains((*x as f64 + 0.5, *y as f64 + 0.5)))
+    }
+
+    /// Part of the pixel covered by the shape (0.0 - 1.0), estimated from a grid of samples.
+    pub fn coverage(&self, x: i32, y: i32) -> f64 {
+        let step = 1.0 / SUBSAMPLES as f64;
+        let mut hits = 0;
+        for i in 0..SUBSAMPLES {
+            for j in 0..SUBSAMPLES {
+                let p = (x as f64 + (i as f64 + 0.5) * step, y as f64 + (j as f64 + 0.5) * step);
+                if self.contains(p) {
+                    hits += 1;
+                }
+            }
+        }
+        hits as f64 / (SUBSAMPLES * SUBSAMPLES) as f64
+    }
+
+    /// All pixels within `bounds` (e.g. from `bounds_within`) touched by the shape with their coverage.
+    /// Without anti-aliasing, pixels are either fully covered or not at all.
+    pub fn covered_pixels(&self, (l, t, r, b): (i32, i32, i32, i32), antialias: bool) -> impl Iterator<Item = (i32, i32, f64)> + '_ {
+        (t..b)
+            .flat_map(move |y| (l..r).map(move |x| (x, y)))
+            .filter_map(move |(x, y)| {
+                let coverage = if antialias {
+                    self.coverage(x, y)
+                } else if self.contains((x as f64 + 0.5, y as f64 + 0.5)) {
+                    1.0
+                } else {
+                    0.0
+                };
+                (coverage > 0.0).then_some((x, y, coverage))
+            })
+    }
+}
+
+fn distance(a: Point, b: Point) -> f64 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+fn extent(points: &[Point]) -> (Point, Point) {
+    let mut min = (f64::INFINITY, f64::INFINITY);
+    let mut max = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for p in points {
+        min = (min.0.min(p.0), min.1.min(p.1));
+        max = (max.0.max(p.0), max.1.max(p.1));
+    }
+    if points.is_empty() {
+        return ((0.0, 0.0), (0.0, 0.0));
+    }
+    (min, max)
+}
+
+/// The offset across the line is half-open (`[-w/2, w/2)`), so that a line of width `w`
+/// covers exactly `w` pixels across when it goes along the pixel grid.
+fn in_segment(p: Point, a: Point, b: Point, width: f64) -> bool {
+    let half = width / 2.0;
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len = (dx * dx + dy * dy).sqrt();
+    let (px, py) = (p.0 - a.0, p.1 - a.1);
+    if len == 0.0 {
+        return -half <= px && px < half && -half <= py && py < half;
+    }
+    let along = (px * dx + py * dy) / len;
+    let across = (px * -dy + py * dx) / len;
+    (0.0..=len).contains(&along) && -half <= across && across < half
+}
+
+fn in_ellipse(dx: f64, dy: f64, rx: f64, ry: f64) -> bool {
+    if rx < 0.0 || ry < 0.0 {
+        return false;
+    }
+    if rx == 0.0 || ry == 0.0 {
+        return dx.abs() <= rx && dy.abs() <= ry;
+    }
+    (dx / rx).powi(2) + (dy / ry).powi(2) <= 1.0
+}
+
+fn in_polygon(p: Point, points: &[Point]) -> bool {
+    let mut inside = false;
+    let n = points.len();
+    for i in 0..n {
+        let a = points[i];
+        let b = points[(i + 1) % n];
+        if (a.1 > p.1) != (b.1 > p.1) {
+            let x = a.0 + (p.1 - a.1) / (b.1 - a.1) * (b.0 - a.0);
+            if p.0 < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// Signed distance from the border of a rounded rectangle, negative inside.
+fn rounded_rect_distance(p: Point, top_left: Point, bottom_right: Point, radius: f64) -> f64 {
+    let half = ((bottom_right.0 - top_left.0) / 2.0, (bottom_right.1 - top_left.1) / 2.0);
+    let center = (top_left.0 + half.0, top_left.1 + half.1);
+    let radius = radius.clamp(0.0, half.0.abs().min(half.1.abs()));
+    let q = (
+        (p.0 - center.0).abs() - half.0.abs() + radius,
+        (p.1 - center.1).abs() - half.1.abs() + radius,
+    );
+    let outside = (q.0.max(0.0).powi(2) + q.1.max(0.0).powi(2)).sqrt();
+    outside + q.0.max(q.1).min(0.0) - radius
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(s: &Shape) -> usize {
+        s.pixels().count()
+    }
+
+    #[test]
+    fn test_line() {
+        // horizontal line through pixel centers
+        let line = Shape::Line { from: (0.5, 5.5), to: (9.5, 5.5), width: 1.0 };
+        assert_eq!(count(&line), 10);
+        assert!(line.pixels().all(|(_, y)| y == 5));
+        let line = Shape::Line { from: (0.5, 5.5), to: (9.5, 5.5), width: 2.0 };
+        assert_eq!(count(&line), 20);
+        let dot = Shape::Line { from: (3.5, 3.5), to: (3.5, 3.5), width: 1.0 };
+        assert_eq!(dot.pixels().collect::<Vec<_>>(), vec![(3, 3)]);
+    }
+
+    #[test]
+    fn test_ellipse() {
+        let circle = Shape::Ellipse { center: (10.5, 10.5), radii: (3.0, 3.0), outline: None };
+        assert!(circle.contains((10.5, 13.5)));
+        assert!(!circle.contains((13.5, 13.5)));
+        assert_eq!(count(&circle), 29);
+        let ring = Shape::Ellipse { center: (10.5, 10.5), radii: (3.0, 3.0), outline: Some(1.0) };
+        assert!(!ring.contains((10.5, 10.5)));
+        assert!(ring.contains((13.5, 10.5)));
+        let point = Shape::Ellipse { center: (1.5, 1.5), radii: (0.0, 0.0), outline: None };
+        assert_eq!(count(&point), 1);
+    }
+
+    #[test]
+    fn test_polygon() {
+        let square = Shape::Polygon(vec![(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+        assert_eq!(count(&square), 16);
+        let triangle = Shape::Polygon(vec![(0.0, 0.0), (10.0, 0.0), (0.0, 10.0)]);
+        assert_eq!(count(&triangle), 45);
+        let outline = Shape::Polyline {
+            points: vec![(0.5, 0.5), (4.5, 0.5), (4.5, 4.5), (0.5, 4.5)],
+            width: 1.0,
+            closed: true,
+        };
+        assert_eq!(count(&outline), 16);
+        assert!(!outline.contains((2.5, 2.5)));
+    }
+
+    #[test]
+    fn test_rounded_rect() {
+        let rect = Shape::RoundedRect { top_left: (0.0, 0.0), bottom_right: (10.0, 10.0), radius: 0.0, outline: None };
+        assert_eq!(count(&rect), 100);
+        let rounded = Shape::RoundedRect { top_left: (0.0, 0.0), bottom_right: (10.0, 10.0), radius: 3.0, outline: None };
+        assert!(!rounded.contains((0.5, 0.5)));
+        assert!(rounded.contains((5.5, 0.5)));
+        let outline = Shape::RoundedRect { top_left: (0.0, 0.0), bottom_right: (10.0, 10.0), radius: 0.0, outline: Some(1.0) };
+        assert_eq!(count(&outline), 36);
+    }
+
+    #[test]
+    fn test_coverage() {
+        let rect = Shape::RoundedRect { top_left: (0.25, 0.0), bottom_right: (2.0, 1.0), radius: 0.0, outline: None };
+        assert_eq!(rect.coverage(0, 0), 0.75);
+        assert_eq!(rect.coverage(1, 0), 1.0);
+        assert_eq!(rect.coverage(2, 0), 0.0);
+        let aliased: Vec<_> = rect.covered_pixels(rect.bounds(), false).collect();
+        assert_eq!(aliased, vec![(0, 0, 1.0), (1, 0, 1.0)]);
+        let smooth: Vec<_> = rect.covered_pixels(rect.bounds(), true).collect();
+        assert_eq!(smooth, vec![(0, 0, 0.75), (1, 0, 1.0)]);
+    }
+
+    #[test]
+    fn test_bounds_within() {
+        let huge = Shape::Ellipse { center: (5.0, 5.0), radii: (i32::MAX as f64, i32::MAX as f64), outline: None };
+        assert_eq!(huge.bounds_within((10, 10)), (0, 0, 10, 10));
+        let outside = Shape::Ellipse { center: (-20.0, 5.0), radii: (3.0, 3.0), outline: None };
+        let (l, _, r, _) = outside.bounds_within((10, 10));
+        assert_eq!(l, r);
+    }
+}
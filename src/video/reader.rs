@@ -1,11 +1,24 @@
 use super::*;
 use ffmpeg_next::{format, frame, software::scaling};
 
+/// Static properties of the processed video.
+#[derive(Debug, Clone, Copy)]
+pub struct VideoInfo {
+    pub width: u32,
+    pub height: u32,
+    pub fps: f64,
+    pub frame_count: usize,
+}
+
 // the lifetime is of a ffmpeg input
 pub struct VideoReader<'a> {
     width: u32,
     height: u32,
     framerate: Rational,
+    /// Unit of the presentation timestamps of decoded frames.
+    time_base: Rational,
+    /// Timestamp of the first decoded frame, times are counted from it.
+    first_timestamp: Option<i64>,
     decoder: ffmpeg_next::decoder::Video,
     stream_index: usize,
     packets: ffmpeg_next::format::context::input::PacketIter<'a>,
@@ -29,20 +42,30 @@ impl<'a> VideoReader<'a> {
         self.framerate
     }
 
-    pub fn new(input: &'a mut ffmpeg_next::format::context::Input) -> Self {
-        let stream = input.streams().best(ffmpeg_next::media::Type::Video).unwrap();
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    pub fn info(&self) -> VideoInfo {
+        VideoInfo { width: self.width, height: self.height, fps: self.framerate.into(), frame_count: self.frame_count }
+    }
+
+    /// Prepare decoding of the best video stream of `input`, the error describes why the input cannot be read.
+    pub fn new(input: &'a mut ffmpeg_next::format::context::Input) -> Result<Self, String> {
+        let stream = input.streams().best(ffmpeg_next::media::Type::Video).ok_or("no video stream found")?;
         let frame_count = stream.frames() as usize;
         if frame_count == 0 {
-            panic!("error: could not retrieve the frame count of video");
+            return Err("could not retrieve the frame count of video".to_string());
         }
         let stream_index = stream.index();
-        let context_decoder = ffmpeg_next::codec::context::Context::from_parameters(stream.parameters()).unwrap();
-        let decoder = context_decoder.decoder().video().unwrap();
+        let time_base = stream.time_base();
+        let context_decoder = ffmpeg_next::codec::context::Context::from_parameters(stream.parameters()).map_err(|e| e.to_string())?;
+        let decoder = context_decoder.decoder().video().map_err(|e| e.to_string())?;
         let width = decoder.width();
         let height = decoder.height();
         let packets = input.packets();
         let current_frame = frame::Video::empty();
-        let framerate = decoder.frame_rate().expect("could not get the video frame rate");
+        let framerate = decoder.frame_rate().ok_or("could not get the video frame rate")?;
         let scaler = scaling::Context::get(
             decoder.format(),
             width,
@@ -51,8 +74,8 @@ impl<'a> VideoReader<'a> {
             width,
             height,
             scaling::Flags::BILINEAR,
-        ).unwrap();
-        Self { width, height, decoder, stream_index, packets, current_frame, scaler, framerate, _eof_sent: false, frame_count, current_frame_index: 0 }
+        ).map_err(|e| e.to_string())?;
+        Ok(Self { width, height, decoder, stream_index, packets, current_frame, scaler, framerate, time_base, first_timestamp: None, _eof_sent: false, frame_count, current_frame_index: 0 })
     }
 
     fn transform_current_frame(&mut self) -> Frame {
@@ -77,6 +100,20 @@ impl<'a> VideoReader<'a> {
         self.current_frame_index
     }
 
+    /// Presentation time of the last decoded frame in milliseconds, counted from the first frame.
+    /// Unlike the frame index, this follows the source timeline even if the frame rate is variable.
+    /// Frames without a timestamp get the time given by their index and the frame rate.
+    pub fn get_frame_time_ms(&mut self) -> usize {
+        let index_time = (self.current_frame_index.saturating_sub(1) as f64 * 1000.0 / f64::from(self.framerate)).round() as usize;
+        let Some(timestamp) = self.current_frame.timestamp() else { return index_time; };
+        let first = *self.first_timestamp.get_or_insert(timestamp);
+        let seconds = (timestamp - first) as f64 * f64::from(self.time_base);
+        if seconds < 0.0 { // e.g. B-frames of a broken stream
+            return index_time;
+        }
+        (seconds * 1000.0).round() as usize
+    }
+
     pub fn get_next_frame(&mut self) -> Option<Frame> {
         if self.decoder.receive_frame(&mut self.current_frame).is_ok() {
             self.current_frame_index += 1;
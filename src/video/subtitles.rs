@@ -0,0 +1,186 @@
+use super::{Canvas, Font, Frame, text};
+use crate::variable::{Color, Position};
+
+/// Text shown from `start` to `end` (in milliseconds, the end is exclusive).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+/// Cues of a SubRip (.srt) or WebVTT (.vtt) file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subtitles {
+    cues: Vec<Cue>,
+}
+
+/// How the subtitles are drawn, unset values depend on the size of the frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtitleStyle {
+    /// Bottom center of the text.
+    pub position: Option<Position>,
+    /// Height of a line in pixels.
+    pub size: Option<u32>,
+    pub color: Color,
+    pub font: Font,
+}
+
+impl SubtitleStyle {
+    pub fn new() -> Self {
+        Self { position: None, size: None, color: Color::from([255, 255, 255]), font: Font::builtin() }
+    }
+}
+
+impl Subtitles {
+    /// Parse the content of an SRT or a WebVTT file, the format is recognized by the `WEBVTT` header.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let content = content.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+        let vtt = content.starts_with("WEBVTT");
+        let mut cues = vec![];
+        for (i, block) in content.split("\n\n").enumerate() {
+            let lines: Vec<&str> = block.lines().filter(|l| !l.trim().is_empty()).collect();
+            if lines.is_empty() || (vtt && i == 0) {
+                continue; // the WebVTT header
+            }
+            if vtt && ["NOTE", "STYLE", "REGION"].iter().any(|k| lines[0].starts_with(k)) {
+                continue;
+            }
+            // the cue number (SRT) or identifier (WebVTT) is optional
+            let Some(timing) = lines.iter().position(|l| l.contains("-->")) else {
+                return Err(format!("cue without timing: \"{}\"", lines[0]));
+            };
+            let (start, end) = parse_timing(lines[timing])?;
+            let text = lines[timing + 1..].iter().map(|l| strip_tags(l, vtt)).collect::<Vec<_>>().join("\n");
+            cues.push(Cue { start, end, text });
+        }
+        cues.sort_by_key(|c| c.start);
+        Ok(Self { cues })
+    }
+
+    pub fn cues(&self) -> &[Cue] {
+        &self.cues
+    }
+
+    /// Text of the cues shown at `time` (in milliseconds), overlapping cues are shown under each other.
+    pub fn text_at(&self, time: usize) -> Option<String> {
+        let active: Vec<&str> = self.cues.iter()
+            .take_while(|c| c.start <= time)
+            .filter(|c| time < c.end)
+            .map(|c| c.text.as_str())
+            .collect();
+        (!active.is_empty()).then(|| active.join("\n"))
+    }
+
+    /// Draw the text shown at `time` on the frame.
+    pub fn draw(&self, frame: &mut Frame, time: usize, style: &SubtitleStyle) {
+        let Some(text) = self.text_at(time) else { return; };
+        let (width, height) = frame.dimensions();
+        let size = style.size.unwrap_or((height / 18).max(8));
+        let img = text::render_text(&text, size, style.color, &style.font);
+        let position = style.position.unwrap_or(Position::new(width as i32 / 2, (height - height / 12) as i32));
+        let x = position.x as i64 - img.width() as i64 / 2;
+        let y = position.y as i64 - img.height() as i64;
+        frame.draw_image(&img, x, y);
+    }
+}
+
+/// Parse `00:00:01,000 --> 00:00:04,000`, WebVTT cue settings after the end are ignored.
+fn parse_timing(line: &str) -> Result<(usize, usize), String> {
+    let (start, rest) = line.split_once("-->").ok_or(format!("invalid cue timing \"{line}\""))?;
+    let end = rest.split_whitespace().next().unwrap_or("");
+    Ok((parse_timestamp(start.trim())?, parse_timestamp(end)?))
+}
+
+/// Parse `hh:mm:ss,mmm` (SRT), `[hh:]mm:ss.mmm` (WebVTT, programs), `90.5s` or `500ms` into milliseconds.
+pub fn parse_timestamp(s: &str) -> Result<usize, String> {
+    let invalid = || format!("invalid timestamp \"{s}\", expected e.g. 00:01:30.500, 90.5s or 500ms");
+    if let Some(ms) = s.strip_suffix("ms") {
+        return ms.parse().map_err(|_| invalid());
+    }
+    if let Some(seconds) = s.strip_suffix('s') {
+        let seconds: f64 = seconds.parse().map_err(|_| invalid())?;
+        if !seconds.is_finite() || seconds < 0.0 {
+            return Err(invalid());
+        }
+        return Ok((seconds * 1000.0).round() as usize);
+    }
+    let (clock, millis) = s.split_once([',', '.']).unwrap_or((s, "0"));
+    // fraction of a second, padded to milliseconds
+    let millis: String = millis.chars().chain("000".chars()).take(3).collect();
+    let millis: usize = millis.parse().map_err(|_| invalid())?;
+    let parts = clock.split(':').map(|p| p.parse::<usize>()).collect::<Result<Vec<_>, _>>().map_err(|_| invalid())?;
+    let seconds = match parts[..] {
+        // minutes and seconds of a clock are below 60
+        [_, m, s] | [m, s] if m >= 60 || s >= 60 => return Err(invalid()),
+        [h, m, s] => h * 3600 + m * 60 + s,
+        [m, s] => m * 60 + s,
+        _ => return Err(invalid()),
+    };
+    Ok(seconds * 1000 + millis)
+}
+
+/// Remove formatting tags (`<i>`, `<b>`, WebVTT voices and classes) and decode WebVTT escapes.
+fn strip_tags(line: &str, vtt: bool) -> String {
+    let mut out = String::new();
+    let mut in_tag = false;
+    for c in line.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    if vtt {
+        out = out.replace("&lt;", "<").replace("&gt;", ">").replace("&nbsp;", " ").replace("&amp;", "&");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_srt() {
+        let srt = "1\r\n00:00:01,000 --> 00:00:04,500\r\nHello <i>there</i>\r\nsecond line\r\n\r\n2\r\n00:00:04,000 --> 00:01:00,000\r\nOverlap\r\n";
+        let s = Subtitles::parse(srt).unwrap();
+        assert_eq!(s.cues()[0], Cue { start: 1000, end: 4500, text: "Hello there\nsecond line".to_string() });
+        assert_eq!(s.cues()[1].end, 60000);
+        assert_eq!(s.text_at(999), None);
+        assert_eq!(s.text_at(1000).unwrap(), "Hello there\nsecond line");
+        assert_eq!(s.text_at(4200).unwrap(), "Hello there\nsecond line\nOverlap");
+        assert_eq!(s.text_at(60000), None);
+    }
+
+    #[test]
+    fn test_parse_vtt() {
+        let vtt = "WEBVTT - title\n\nNOTE a comment\n\nintro\n00:01.000 --> 00:02.250 position:10% align:start\n<v Roger>Fish &amp; chips\n\n01:00:00.000 --> 01:00:01.000\nlate\n";
+        let s = Subtitles::parse(vtt).unwrap();
+        assert_eq!(s.cues().len(), 2);
+        assert_eq!(s.cues()[0], Cue { start: 1000, end: 2250, text: "Fish & chips".to_string() });
+        assert_eq!(s.cues()[1].start, 3_600_000);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Subtitles::parse("1\n00:00:01,000 -> 00:00:02,000\nx\n").is_err());
+        assert!(Subtitles::parse("1\n00:00:01,000 --> 00:00:75,000\nx\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("00:01:30.500"), Ok(90_500));
+        assert_eq!(parse_timestamp("00:01:30,500"), Ok(90_500));
+        assert_eq!(parse_timestamp("00:00:02.5"), Ok(2500));
+        assert_eq!(parse_timestamp("1:30"), Ok(90_000));
+        assert_eq!(parse_timestamp("90.5s"), Ok(90_500));
+        assert_eq!(parse_timestamp("500ms"), Ok(500));
+        assert!(parse_timestamp("00:99:00").is_err());
+        assert!(parse_timestamp("1:2:3:4.5").is_err());
+        assert!(parse_timestamp("-1s").is_err());
+        assert!(parse_timestamp("1.5ms").is_err());
+        assert!(parse_timestamp("10").is_err());
+    }
+}
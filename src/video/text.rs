@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use image::Rgba;
+
+use super::Image;
+use crate::variable::Color;
+
+/// Classic 5x7 pixel font for printable ASCII (0x20 - 0x7e). Each glyph is 5 columns,
+/// bit 0 of a column is its top pixel.
+const FONT: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], [0x00, 0x00, 0x5f, 0x00, 0x00], [0x00, 0x07, 0x00, 0x07, 0x00], [0x14, 0x7f, 0x14, 0x7f, 0x14],
+    [0x24, 0x2a, 0x7f, 0x2a, 0x12], [0x23, 0x13, 0x08, 0x64, 0x62], [0x36, 0x49, 0x55, 0x22, 0x50], [0x00, 0x05, 0x03, 0x00, 0x00],
+    [0x00, 0x1c, 0x22, 0x41, 0x00], [0x00, 0x41, 0x22, 0x1c, 0x00], [0x14, 0x08, 0x3e, 0x08, 0x14], [0x08, 0x08, 0x3e, 0x08, 0x08],
+    [0x00, 0x50, 0x30, 0x00, 0x00], [0x08, 0x08, 0x08, 0x08, 0x08], [0x00, 0x60, 0x60, 0x00, 0x00], [0x20, 0x10, 0x08, 0x04, 0x02],
+    [0x3e, 0x51, 0x49, 0x45, 0x3e], [0x00, 0x42, 0x7f, 0x40, 0x00], [0x42, 0x61, 0x51, 0x49, 0x46], [0x21, 0x41, 0x45, 0x4b, 0x31],
+    [0x18, 0x14, 0x12, 0x7f, 0x10], [0x27, 0x45, 0x45, 0x45, 0x39], [0x3c, 0x4a, 0x49, 0x49, 0x30], [0x01, 0x71, 0x09, 0x05, 0x03],
+    [0x36, 0x49, 0x49, 0x49, 0x36], [0x06, 0x49, 0x49, 0x29, 0x1e], [0x00, 0x36, 0x36, 0x00, 0x00], [0x00, 0x56, 0x36, 0x00, 0x00],
+    [0x08, 0x14, 0x22, 0x41, 0x00], [0x14, 0x14, 0x14, 0x14, 0x14], [0x00, 0x41, 0x22, 0x14, 0x08], [0x02, 0x01, 0x51, 0x09, 0x06],
+    [0x32, 0x49, 0x79, 0x41, 0x3e], [0x7e, 0x11, 0x11, 0x11, 0x7e], [0x7f, 0x49, 0x49, 0x49, 0x36], [0x3e, 0x41, 0x41, 0x41, 0x22],
+    [0x7f, 0x41, 0x41, 0x22, 0x1c], [0x7f, 0x49, 0x49, 0x49, 0x41], [0x7f, 0x09, 0x09, 0x09, 0x01], [0x3e, 0x41, 0x49, 0x49, 0x7a],
+    [0x7f, 0x08, 0x08, 0x08, 0x7f], [0x00, 0x41, 0x7f, 0x41, 0x00], [0x20, 0x40, 0x41, 0x3f, 0x01], [0x7f, 0x08, 0x14, 0x22, 0x41],
+    [0x7f, 0x40, 0x40, 0x40, 0x40], [0x7f, 0x02, 0x0c, 0x02, 0x7f], [0x7f, 0x04, 0x08, 0x10, 0x7f], [0x3e, 0x41, 0x41, 0x41, 0x3e],
+    [0x7f, 0x09, 0x09, 0x09, 0x06], [0x3e, 0x41, 0x51, 0x21, 0x5e], [0x7f, 0x09, 0x19, 0x29, 0x46], [0x46, 0x49, 0x49, 0x49, 0x31],
+    [0x01, 0x01, 0x7f, 0x01, 0x01], [0x3f, 0x40, 0x40, 0x40, 0x3f], [0x1f, 0x20, 0x40, 0x20, 0x1f], [0x3f, 0x40, 0x38, 0x40, 0x3f],
+    [0x63, 0x14, 0x08, 0x14, 0x63], [0x07, 0x08, 0x70, 0x08, 0x07], [0x61, 0x51, 0x49, 0x45, 0x43], [0x00, 0x7f, 0x41, 0x41, 0x00],
+    [0x02, 0x04, 0x08, 0x10, 0x20], [0x00, 0x41, 0x41, 0x7f, 0x00], [0x04, 0x02, 0x01, 0x02, 0x04], [0x40, 0x40, 0x40, 0x40, 0x40],
+    [0x00, 0x01, 0x02, 0x04, 0x00], [0x20, 0x54, 0x54, 0x54, 0x78], [0x7f, 0x48, 0x44, 0x44, 0x38], [0x38, 0x44, 0x44, 0x44, 0x20],
+    [0x38, 0x44, 0x44, 0x48, 0x7f], [0x38, 0x54, 0x54, 0x54, 0x18], [0x08, 0x7e, 0x09, 0x01, 0x02], [0x0c, 0x52, 0x52, 0x52, 0x3e],
+    [0x7f, 0x08, 0x04, 0x04, 0x78], [0x00, 0x44, 0x7d, 0x40, 0x00], [0x20, 0x40, 0x44, 0x3d, 0x00], [0x7f, 0x10, 0x28, 0x44, 0x00],
+    [0x00, 0x41, 0x7f, 0x40, 0x00], [0x7c, 0x04, 0x18, 0x04, 0x78], [0x7c, 0x08, 0x04, 0x04, 0x78], [0x38, 0x44, 0x44, 0x44, 0x38],
+    [0x7c, 0x14, 0x14, 0x14, 0x08], [0x08, 0x14, 0x14, 0x18, 0x7c], [0x7c, 0x08, 0x04, 0x04, 0x08], [0x48, 0x54, 0x54, 0x54, 0x20],
+    [0x04, 0x3f, 0x44, 0x40, 0x20], [0x3c, 0x40, 0x40, 0x20, 0x7c], [0x1c, 0x20, 0x40, 0x20, 0x1c], [0x3c, 0x40, 0x30, 0x40, 0x3c],
+    [0x44, 0x28, 0x10, 0x28, 0x44], [0x0c, 0x50, 0x50, 0x50, 0x3c], [0x44, 0x64, 0x54, 0x4c, 0x44], [0x00, 0x08, 0x36, 0x41, 0x00],
+    [0x00, 0x00, 0x7f, 0x00, 0x00], [0x00, 0x41, 0x36, 0x08, 0x00], [0x10, 0x08, 0x08, 0x10, 0x08],
+];
+
+/// Monospace bitmap font, every glyph is `width` x `height` pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Font {
+    width: usize,
+    height: usize,
+    /// Lit pixels of each glyph, row by row.
+    glyphs: HashMap<char, Vec<bool>>,
+}
+
+impl Font {
+    /// The built-in 5x7 font, it only has printable ASCII characters.
+    pub fn builtin() -> Self {
+        let glyphs = (' '..='~').zip(FONT.iter()).map(|(c, columns)| {
+            let pixels = (0..7).flat_map(|row| columns.iter().map(move |col| col >> row & 1 == 1)).collect();
+            (c, pixels)
+        }).collect();
+        Self { width: 5, height: 7, glyphs }
+    }
+
+    /// Read a font in the Glyph Bitmap Distribution Format (.bdf), encodings are taken as Unicode.
+    /// All glyphs are placed in the bounding box of the font, so proportional fonts are drawn as monospace.
+    pub fn parse_bdf(content: &str) -> Result<Self, String> {
+        let numbers = |line: &str, count: usize| -> Result<Vec<i64>, String> {
+            let values: Vec<i64> = line.split_whitespace().skip(1).map(|x| x.parse()).collect::<Result<_, _>>()
+                .map_err(|_| format!("invalid numbers in \"{line}\""))?;
+            if values.len() < count {
+                return Err(format!("expected {count} numbers in \"{line}\""));
+            }
+            Ok(values)
+        };
+        let mut lines = content.lines().map(str::trim);
+        if !lines.next().is_some_and(|l| l.starts_with("STARTFONT")) {
+            return Err("missing STARTFONT header".to_string());
+        }
+        let mut bounds = None; // width, height, x offset, y offset
+        let mut glyphs = HashMap::new();
+        let mut encoding = None;
+        let mut bbx = None;
+        while let Some(line) = lines.next() {
+            let keyword = line.split_whitespace().next().unwrap_or("");
+            match keyword {
+                "FONTBOUNDINGBOX" => bounds = Some(numbers(line, 4)?),
+                "STARTCHAR" => (encoding, bbx) = (None, None),
+                "ENCODING" => encoding = Some(numbers(line, 1)?[0]),
+                "BBX" => bbx = Some(numbers(line, 4)?),
+                "BITMAP" => {
+                    let Some(b) = &bounds else {
+                        return Err("BITMAP before FONTBOUNDINGBOX".to_string());
+                    };
+                    let g = bbx.clone().unwrap_or_else(|| b.clone());
+                    let (width, height) = (b[0].max(0) as usize, b[1].max(0) as usize);
+                    let mut pixels = vec![false; width * height];
+                    for (row, line) in lines.by_ref().enumerate() {
+                        let row = row as i64;
+                        if line == "ENDCHAR" {
+                            break;
+                        }
+                        let bits = u128::from_str_radix(line, 16).map_err(|_| format!("invalid bitmap row \"{line}\""))?;
+                        let bit_count = line.len() as i64 * 4;
+                        // the top row of the glyph is `g[3] + g[1] - 1` above the baseline
+                        let y = (b[3] + b[1] - 1) - (g[3] + g[1] - 1 - row);
+                        for col in 0..g[0].min(bit_count) {
+                            let x = g[2] - b[2] + col;
+                            let lit = bits >> (bit_count - 1 - col) & 1 == 1;
+                            if lit && (0..width as i64).contains(&x) && (0..height as i64).contains(&y) {
+                                pixels[y as usize * width + x as usize] = true;
+                            }
+                        }
+                    }
+                    if let Some(c) = encoding.and_then(|e| u32::try_from(e).ok()).and_then(char::from_u32) {
+                        glyphs.insert(c, pixels);
+                    }
+                }
+                _ => {}
+            }
+        }
+        let Some(b) = bounds else {
+            return Err("missing FONTBOUNDINGBOX".to_string());
+        };
+        Ok(Self { width: b[0].max(0) as usize, height: b[1].max(0) as usize, glyphs })
+    }
+
+    /// Whether the pixel of the glyph is lit, missing characters are drawn as `?`.
+    fn is_lit(&self, c: char, x: usize, y: usize) -> bool {
+        self.glyphs.get(&c).or(self.glyphs.get(&'?'))
+            .is_some_and(|pixels| pixels[y * self.width + x])
+    }
+}
+
+/// Render the text with a pixel font, `size` is the height of a line in pixels.
+/// Lines are centered and the text gets a black outline, so it is readable on any background.
+/// Characters the font does not have are drawn as `?`.
+pub fn render_text(text: &str, size: u32, color: Color, font: &Font) -> Image {
+    // a character takes one more pixel in each direction, the gaps between characters and lines
+    let cell = (font.width + 1, font.height + 1);
+    let lines: Vec<Vec<char>> = text.lines().map(|l| l.chars().collect()).collect();
+    let unit = size.max(1) as f64 / cell.1 as f64;
+    let outline = (unit / 2.0).round().max(1.0) as i64;
+    let longest = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+    let line_width = |len: usize| ((len * cell.0).saturating_sub(1) as f64 * unit).ceil() as i64;
+    let width = line_width(longest) + 2 * outline;
+    let height = lines.len() as i64 * size as i64 + 2 * outline;
+
+    // which pixels are covered by the font
+    let lit = |x: i64, y: i64| -> bool {
+        let (x, y) = (x - outline, y - outline);
+        if x < 0 || y < 0 {
+            return false;
+        }
+        let Some(line) = lines.get((y / size.max(1) as i64) as usize) else { return false; };
+        let x = x - (line_width(longest) - line_width(line.len())) / 2;
+        let (col, row) = ((x.max(0) as f64 / unit) as usize, ((y % size.max(1) as i64) as f64 / unit) as usize);
+        if x < 0 || row >= font.height || col % cell.0 >= font.width {
+            return false;
+        }
+        line.get(col / cell.0).is_some_and(|c| font.is_lit(*c, col % cell.0, row))
+    };
+    let mask: Vec<bool> = (0..height).flat_map(|y| (0..width).map(move |x| (x, y))).map(|(x, y)| lit(x, y)).collect();
+    let at = |x: i64, y: i64| x >= 0 && y >= 0 && x < width && y < height && mask[(y * width + x) as usize];
+
+    Image::from_fn(width as u32, height as u32, |x, y| {
+        let (x, y) = (x as i64, y as i64);
+        if at(x, y) {
+            Rgba([color[0], color[1], color[2], 255])
+        } else if (-outline..=outline).any(|dy| (-outline..=outline).any(|dx| at(x + dx, y + dy))) {
+            Rgba([0, 0, 0, 255])
+        } else {
+            Rgba([0, 0, 0, 0])
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_text() {
+        let white = Color::from([255, 255, 255]);
+        let img = render_text("I", 8, white, &Font::builtin());
+        // 5 pixels wide glyph with 1 pixel outline
+        assert_eq!(img.dimensions(), (7, 10));
+        // the middle column of `I` is lit, the corners are transparent
+        assert_eq!(*img.get_pixel(3, 1), Rgba([255, 255, 255, 255]));
+        assert_eq!(*img.get_pixel(3, 8), Rgba([0, 0, 0, 255]));
+        assert_eq!(img.get_pixel(0, 9)[3], 0);
+
+        let img = render_text("ab\nc", 16, white, &Font::builtin());
+        assert_eq!(img.dimensions(), (22 + 2, 32 + 2));
+    }
+
+    #[test]
+    fn test_parse_bdf() {
+        let bdf = "STARTFONT 2.1
+FONTBOUNDINGBOX 4 4 0 -1
+STARTCHAR uni010D
+ENCODING 269
+BBX 3 2 1 0
+BITMAP
+E0
+40
+ENDCHAR
+STARTCHAR question
+ENCODING 63
+BITMAP
+F0
+00
+00
+F0
+ENDCHAR
+ENDFONT
+";
+        let font = Font::parse_bdf(bdf).unwrap();
+        // rows 1 and 2 of the box are 1 and 0 pixels above the baseline
+        assert!(font.is_lit('č', 1, 1) && font.is_lit('č', 3, 1) && font.is_lit('č', 2, 2));
+        assert!(!font.is_lit('č', 0, 1) && !font.is_lit('č', 1, 2) && !font.is_lit('č', 2, 0));
+        // missing characters fall back to `?`
+        assert!(font.is_lit('x', 0, 0) && font.is_lit('x', 3, 3) && !font.is_lit('x', 0, 1));
+        let img = render_text("č", 5, Color::from([255, 255, 255]), &font);
+        assert_eq!(img.dimensions(), (4 + 2, 5 + 2));
+
+        assert!(Font::parse_bdf("FONTBOUNDINGBOX 4 4 0 -1").is_err());
+        assert!(Font::parse_bdf("STARTFONT 2.1\nENCODING 65\nBITMAP\n").is_err());
+        assert!(Font::parse_bdf("STARTFONT 2.1\nFONTBOUNDINGBOX 4 4 0 0\nENCODING 65\nBITMAP\nzz\n").is_err());
+    }
+}
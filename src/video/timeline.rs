@@ -0,0 +1,192 @@
+use std::collections::VecDeque;
+
+use super::Frame;
+use crate::variable::color;
+
+/// Decides which input frame is shown in each output frame (speed changes, freezing, reversing).
+/// Frames are numbered from 1 in the order they are read.
+#[derive(Debug, Clone)]
+pub struct Timeline {
+    speed: f64,
+    /// Whether frames are blended when changing speed instead of being duplicated or dropped.
+    blend: bool,
+    /// Position of the next output frame in the input, in frames (0.0 is the first frame).
+    position: f64,
+    /// Read frames which may be still shown, with their numbers, oldest first.
+    buffer: VecDeque<(usize, Frame)>,
+    /// Number of frames read from the input.
+    read: usize,
+    /// The input frame shown last, with its number.
+    shown: Option<(usize, Frame)>,
+    /// For how many more output frames is the shown frame repeated.
+    frozen: usize,
+    /// Segments `(from, to)` to be played backwards once they are reached.
+    pending_reverse: Vec<(usize, usize)>,
+    /// Frames of the segment being played backwards, the next one last.
+    reversed: Vec<(usize, Frame)>,
+}
+
+impl Timeline {
+    pub fn new() -> Self {
+        Self {
+            speed: 1.0,
+            blend: false,
+            position: 0.0,
+            buffer: VecDeque::new(),
+            read: 0,
+            shown: None,
+            frozen: 0,
+            pending_reverse: vec![],
+            reversed: vec![],
+        }
+    }
+
+    /// Number of the input frame shown last, 0 before the first frame.
+    pub fn current_index(&self) -> usize {
+        self.shown.as_ref().map_or(0, |(i, _)| *i)
+    }
+
+    pub fn set_speed(&mut self, speed: f64, blend: bool) {
+        self.speed = speed;
+        self.blend = blend;
+    }
+
+    /// Show the current input frame for `frames` more output frames.
+    pub fn freeze(&mut self, frames: usize) {
+        self.frozen += frames;
+    }
+
+    /// Play the input frames `from` to `to` (inclusive) backwards once they are reached.
+    pub fn reverse(&mut self, from: usize, to: usize) {
+        self.pending_reverse.push((from, to));
+        self.pending_reverse.sort();
+    }
+
+    /// Next output frame, frames are read with `read` as they are needed.
+    /// Returns `None` once the input is exhausted.
+    pub fn next_frame<F: FnMut() -> Option<Frame>>(&mut self, mut read: F) -> Option<Frame> {
+        if self.frozen > 0 && let Some((_, f)) = &self.shown {
+            self.frozen -= 1;
+            return Some(f.clone());
+        }
+        if self.reversed.is_empty() {
+            self.start_reverse(&mut read);
+        }
+        if let Some((i, f)) = self.reversed.pop() {
+            let out = f.clone();
+            self.shown = Some((i, f));
+            return Some(out);
+        }
+
+        let index = self.position.floor() as usize + 1;
+        let lookahead = if self.blend && self.speed < 1.0 { 1 } else { 0 };
+        self.fill(index + lookahead, &mut read);
+        let previous = self.current_index();
+        let mut passed = vec![];
+        while self.buffer.front().is_some_and(|(i, _)| *i < index) {
+            passed.extend(self.buffer.pop_front().filter(|(i, _)| *i > previous));
+        }
+        let (i, f) = self.buffer.front()?;
+        let out = if self.blend && self.speed > 1.0 {
+            // the frames skipped since the last output frame are averaged in
+            passed.push((*i, f.clone()));
+            average(passed.iter().map(|(_, f)| f))
+        } else if self.blend && self.speed < 1.0 && let Some((_, next)) = self.buffer.get(1) {
+            let t = self.position.fract();
+            Frame::from_fn(f.width(), f.height(), |x, y| color::mix(*f.get_pixel(x, y), *next.get_pixel(x, y), t))
+        } else {
+            f.clone()
+        };
+        self.shown = Some((*i, f.clone()));
+        self.position += self.speed;
+        Some(out)
+    }
+
+    /// Read frames until the frame `index` is read (or the input ends).
+    fn fill<F: FnMut() -> Option<Frame>>(&mut self, index: usize, read: &mut F) {
+        while self.read < index {
+            let Some(f) = read() else { return; };
+            self.read += 1;
+            self.buffer.push_back((self.read, f));
+        }
+    }
+
+    /// Prepare the reversed segment if the next frame starts it.
+    fn start_reverse<F: FnMut() -> Option<Frame>>(&mut self, read: &mut F) {
+        let next = self.position.floor() as usize + 1;
+        let Some(&(from, to)) = self.pending_reverse.first() else { return; };
+        if from > next {
+            return;
+        }
+        self.pending_reverse.remove(0);
+        self.fill(to, read);
+        while self.buffer.front().is_some_and(|(i, _)| *i <= to) {
+            let (i, f) = self.buffer.pop_front().unwrap();
+            if i >= from {
+                self.reversed.push((i, f));
+            }
+        }
+        // continue after the segment, as if it was played forwards
+        self.position = self.position.max(to as f64);
+    }
+}
+
+fn average<'a, I: Iterator<Item = &'a Frame>>(frames: I) -> Frame {
+    let frames: Vec<&Frame> = frames.collect();
+    let (w, h) = frames[0].dimensions();
+    let n = frames.len() as f64;
+    Frame::from_fn(w, h, |x, y| {
+        let mut sum = [0.0; 3];
+        for f in &frames {
+            let p = f.get_pixel(x, y);
+            for i in 0..3 { sum[i] += p[i] as f64; }
+        }
+        image::Rgb(sum.map(|s| (s / n).round() as u8))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Input of `n` 1x1 frames, the red channel is the number of the frame.
+    fn input(n: u8) -> impl FnMut() -> Option<Frame> {
+        let mut i = 0;
+        move || {
+            i += 1;
+            (i <= n).then(|| Frame::from_pixel(1, 1, image::Rgb([i, 0, 0])))
+        }
+    }
+
+    fn play(t: &mut Timeline, n: u8) -> Vec<u8> {
+        let mut read = input(n);
+        std::iter::from_fn(|| t.next_frame(&mut read)).map(|f| f.get_pixel(0, 0)[0]).collect()
+    }
+
+    #[test]
+    fn test_speed() {
+        let mut t = Timeline::new();
+        t.set_speed(2.0, false);
+        assert_eq!(play(&mut t, 6), vec![1, 3, 5]);
+        let mut t = Timeline::new();
+        t.set_speed(0.5, false);
+        assert_eq!(play(&mut t, 3), vec![1, 1, 2, 2, 3, 3]);
+        let mut t = Timeline::new();
+        t.set_speed(2.0, true);
+        assert_eq!(play(&mut t, 6), vec![1, 3, 5]);
+        let mut t = Timeline::new();
+        t.set_speed(0.5, true);
+        assert_eq!(play(&mut t, 2), vec![1, 2, 2, 2]);
+    }
+
+    #[test]
+    fn test_freeze_and_reverse() {
+        let mut t = Timeline::new();
+        let mut read = input(6);
+        t.next_frame(&mut read);
+        t.freeze(2);
+        t.reverse(3, 5);
+        let rest: Vec<u8> = std::iter::from_fn(|| t.next_frame(&mut read)).map(|f| f.get_pixel(0, 0)[0]).collect();
+        assert_eq!(rest, vec![1, 1, 2, 5, 4, 3, 6]);
+    }
+}
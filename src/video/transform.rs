@@ -0,0 +1,126 @@
+use image::{ImageBuffer, Pixel, imageops::{self, FilterType}};
+
+/// Geometric transformations of whole images, done in place.
+pub trait Transformable {
+    /// Resize the image to exactly `width` x `height` (bicubic filtering).
+    fn scale_to(&mut self, width: u32, height: u32);
+    /// Resize the image by a factor, keeping its aspect ratio.
+    fn scale_by(&mut self, factor: f64);
+    /// Rotate the image clockwise, the image grows so that nothing is cut off,
+    /// uncovered corners are zeroed (black, or transparent with alpha).
+    fn rotate_by(&mut self, degrees: i32);
+    fn flip_horizontally(&mut self);
+    fn flip_vertically(&mut self);
+}
+
+impl<P> Transformable for ImageBuffer<P, Vec<u8>>
+where
+    P: Pixel<Subpixel = u8> + 'static,
+{
+    fn scale_to(&mut self, width: u32, height: u32) {
+        if (width, height) == self.dimensions() {
+            return;
+        }
+        *self = imageops::resize(self, width, height, FilterType::CatmullRom);
+    }
+
+    fn scale_by(&mut self, factor: f64) {
+        let width = (self.width() as f64 * factor).round().max(0.0) as u32;
+        let height = (self.height() as f64 * factor).round().max(0.0) as u32;
+        self.scale_to(width, height);
+    }
+
+    fn rotate_by(&mut self, degrees: i32) {
+        match degrees.rem_euclid(360) {
+            0 => {}
+            90 => *self = imageops::rotate90(self),
+            180 => imageops::rotate180_in_place(self),
+            270 => *self = imageops::rotate270(self),
+            d => *self = rotate_bilinear(self, d as f64),
+        }
+    }
+
+    fn flip_horizontally(&mut self) {
+        imageops::flip_horizontal_in_place(self);
+    }
+
+    fn flip_vertically(&mut self) {
+        imageops::flip_vertical_in_place(self);
+    }
+}
+
+/// Rotate by an arbitrary angle, sampling the source with bilinear interpolation.
+fn rotate_bilinear<P>(img: &ImageBuffer<P, Vec<u8>>, degrees: f64) -> ImageBuffer<P, Vec<u8>>
+where
+    P: Pixel<Subpixel = u8> + 'static,
+{
+    let (w, h) = (img.width() as f64, img.height() as f64);
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let out_w = (w * cos.abs() + h * sin.abs()).round() as u32;
+    let out_h = (w * sin.abs() + h * cos.abs()).round() as u32;
+    let (cx, cy) = (w / 2.0, h / 2.0);
+    let (ocx, ocy) = (out_w as f64 / 2.0, out_h as f64 / 2.0);
+    let channels = P::CHANNEL_COUNT as usize;
+    ImageBuffer::from_fn(out_w, out_h, |x, y| {
+        // map the output pixel center back into the source image
+        let (dx, dy) = (x as f64 + 0.5 - ocx, y as f64 + 0.5 - ocy);
+        let sx = dx * cos + dy * sin + cx - 0.5;
+        let sy = -dx * sin + dy * cos + cy - 0.5;
+        let mut out = vec![0u8; channels];
+        if sx < -0.5 || sy < -0.5 || sx > w - 0.5 || sy > h - 0.5 {
+            return *P::from_slice(&out);
+        }
+        let (x0, y0) = (sx.floor(), sy.floor());
+        let (fx, fy) = (sx - x0, sy - y0);
+        let sample = |x: f64, y: f64| {
+            let x = (x as i64).clamp(0, img.width() as i64 - 1) as u32;
+            let y = (y as i64).clamp(0, img.height() as i64 - 1) as u32;
+            img.get_pixel(x, y).channels().to_vec()
+        };
+        let (p00, p10) = (sample(x0, y0), sample(x0 + 1.0, y0));
+        let (p01, p11) = (sample(x0, y0 + 1.0), sample(x0 + 1.0, y0 + 1.0));
+        for i in 0..channels {
+            let top = p00[i] as f64 * (1.0 - fx) + p10[i] as f64 * fx;
+            let bottom = p01[i] as f64 * (1.0 - fx) + p11[i] as f64 * fx;
+            out[i] = (top * (1.0 - fy) + bottom * fy).round() as u8;
+        }
+        *P::from_slice(&out)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn test_scale() {
+        let mut img = RgbImage::from_pixel(4, 2, Rgb([10, 20, 30]));
+        img.scale_by(2.5);
+        assert_eq!(img.dimensions(), (10, 5));
+        assert_eq!(*img.get_pixel(5, 2), Rgb([10, 20, 30]));
+        img.scale_to(3, 7);
+        assert_eq!(img.dimensions(), (3, 7));
+    }
+
+    #[test]
+    fn test_rotate_and_flip() {
+        let mut img = RgbImage::new(3, 2);
+        img.put_pixel(0, 0, Rgb([255, 0, 0]));
+        img.rotate_by(90);
+        assert_eq!(img.dimensions(), (2, 3));
+        assert_eq!(*img.get_pixel(1, 0), Rgb([255, 0, 0]));
+        img.rotate_by(-90);
+        assert_eq!(*img.get_pixel(0, 0), Rgb([255, 0, 0]));
+        img.flip_horizontally();
+        assert_eq!(*img.get_pixel(2, 0), Rgb([255, 0, 0]));
+        img.flip_vertically();
+        assert_eq!(*img.get_pixel(2, 1), Rgb([255, 0, 0]));
+
+        let mut img = RgbImage::from_pixel(10, 10, Rgb([255, 255, 255]));
+        img.rotate_by(45);
+        assert_eq!(img.dimensions(), (14, 14));
+        assert_eq!(*img.get_pixel(7, 7), Rgb([255, 255, 255]));
+        assert_eq!(*img.get_pixel(0, 0), Rgb([0, 0, 0]));
+    }
+}
@@ -0,0 +1,127 @@
+use super::Frame;
+use crate::variable::{Direction, Transition, color};
+
+/// Picture of the transition from `from` to `to` at `progress` (0.0 is `from`, 1.0 is `to`).
+/// Both frames have to have the same size.
+pub fn blend(transition: &Transition, from: &Frame, to: &Frame, progress: f64) -> Frame {
+    assert_eq!(from.dimensions(), to.dimensions(), "transition between frames of different sizes");
+    let t = progress.clamp(0.0, 1.0);
+    let (w, h) = from.dimensions();
+    let (fw, fh) = (w as f64, h as f64);
+    match *transition {
+        Transition::Crossfade => Frame::from_fn(w, h, |x, y| color::mix(*from.get_pixel(x, y), *to.get_pixel(x, y), t)),
+        Transition::Dip(c) => Frame::from_fn(w, h, |x, y| {
+            if t < 0.5 {
+                color::mix(*from.get_pixel(x, y), c, t * 2.0)
+            } else {
+                color::mix(c, *to.get_pixel(x, y), t * 2.0 - 1.0)
+            }
+        }),
+        Transition::Wipe(d) => Frame::from_fn(w, h, |x, y| {
+            let (x_, y_) = (x as f64 + 0.5, y as f64 + 0.5);
+            let uncovered = match d {
+                Direction::Right => x_ < t * fw,
+                Direction::Left => x_ > (1.0 - t) * fw,
+                Direction::Down => y_ < t * fh,
+                Direction::Up => y_ > (1.0 - t) * fh,
+            };
+            if uncovered { *to.get_pixel(x, y) } else { *from.get_pixel(x, y) }
+        }),
+        Transition::Slide(d) => {
+            // how far the pictures moved, the second picture starts right behind the first one
+            let (dx, dy) = match d {
+                Direction::Right => ((t * fw).round() as i64, 0),
+                Direction::Left => (-(t * fw).round() as i64, 0),
+                Direction::Down => (0, (t * fh).round() as i64),
+                Direction::Up => (0, -(t * fh).round() as i64),
+            };
+            let (w_, h_) = (w as i64, h as i64);
+            Frame::from_fn(w, h, |x, y| {
+                let (sx, sy) = (x as i64 - dx, y as i64 - dy);
+                if sx >= 0 && sy >= 0 && sx < w_ && sy < h_ {
+                    *from.get_pixel(sx as u32, sy as u32)
+                } else {
+                    *to.get_pixel(sx.rem_euclid(w_) as u32, sy.rem_euclid(h_) as u32)
+                }
+            })
+        }
+        Transition::Zoom => {
+            let (cx, cy) = (fw / 2.0, fh / 2.0);
+            Frame::from_fn(w, h, |x, y| {
+                if t <= 0.0 {
+                    return *from.get_pixel(x, y);
+                }
+                let sx = cx + (x as f64 + 0.5 - cx) / t;
+                let sy = cy + (y as f64 + 0.5 - cy) / t;
+                if sx < 0.0 || sy < 0.0 || sx >= fw || sy >= fh {
+                    *from.get_pixel(x, y)
+                } else {
+                    *to.get_pixel(sx as u32, sy as u32)
+                }
+            })
+        }
+    }
+}
+
+/// Transition between the processed frames and a still picture, it progresses by one step
+/// with every finished frame.
+#[derive(Debug, Clone)]
+pub struct RunningTransition {
+    transition: Transition,
+    picture: Frame,
+    /// Whether the frames turn into the picture (or the picture into the frames).
+    to_picture: bool,
+    duration: usize,
+    elapsed: usize,
+}
+
+impl RunningTransition {
+    pub fn new(transition: Transition, picture: Frame, to_picture: bool, duration: usize) -> Self {
+        Self { transition, picture, to_picture, duration: duration.max(1), elapsed: 0 }
+    }
+
+    /// Replace the frame by the next step of the transition, the last step shows only the target.
+    pub fn apply(&mut self, frame: &mut Frame) {
+        self.elapsed += 1;
+        let progress = self.elapsed as f64 / self.duration as f64;
+        *frame = if self.to_picture {
+            blend(&self.transition, frame, &self.picture, progress)
+        } else {
+            blend(&self.transition, &self.picture, frame, progress)
+        };
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variable::Color;
+
+    const BLACK: Color = image::Rgb([0, 0, 0]);
+    const WHITE: Color = image::Rgb([255, 255, 255]);
+
+    #[test]
+    fn test_blend() {
+        let (from, to) = (Frame::from_pixel(4, 2, BLACK), Frame::from_pixel(4, 2, WHITE));
+        let all = |f: &Frame, c: Color| f.pixels().all(|p| *p == c);
+        for t in [Transition::Crossfade, Transition::Dip(BLACK), Transition::Wipe(Direction::Up),
+                  Transition::Slide(Direction::Left), Transition::Zoom] {
+            assert!(all(&blend(&t, &from, &to, 0.0), BLACK), "{t} at start");
+            assert!(all(&blend(&t, &from, &to, 1.0), WHITE), "{t} at end");
+        }
+        assert!(all(&blend(&Transition::Crossfade, &from, &to, 0.5), Color::from([128, 128, 128])));
+        let red = Color::from([255, 0, 0]);
+        assert!(all(&blend(&Transition::Dip(red), &from, &to, 0.5), red));
+
+        let wiped = blend(&Transition::Wipe(Direction::Right), &from, &to, 0.5);
+        assert_eq!((*wiped.get_pixel(1, 0), *wiped.get_pixel(2, 0)), (WHITE, BLACK));
+        let slid = blend(&Transition::Slide(Direction::Left), &from, &to, 0.25);
+        assert_eq!((*slid.get_pixel(2, 0), *slid.get_pixel(3, 0)), (BLACK, WHITE));
+        let zoomed = blend(&Transition::Zoom, &from, &to, 0.5);
+        assert_eq!((*zoomed.get_pixel(0, 0), *zoomed.get_pixel(1, 0)), (BLACK, WHITE));
+    }
+}